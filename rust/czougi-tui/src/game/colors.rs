@@ -0,0 +1,155 @@
+use crossterm::style::Color;
+use std::env;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+const ANSI_16: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::DarkRed, [128, 0, 0]),
+    (Color::DarkGreen, [0, 128, 0]),
+    (Color::DarkYellow, [128, 128, 0]),
+    (Color::DarkBlue, [0, 0, 128]),
+    (Color::DarkMagenta, [128, 0, 128]),
+    (Color::DarkCyan, [0, 128, 128]),
+    (Color::Grey, [192, 192, 192]),
+    (Color::DarkGrey, [128, 128, 128]),
+    (Color::Red, [255, 0, 0]),
+    (Color::Green, [0, 255, 0]),
+    (Color::Yellow, [255, 255, 0]),
+    (Color::Blue, [0, 0, 255]),
+    (Color::Magenta, [255, 0, 255]),
+    (Color::Cyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+// Channel values of the 6x6x6 cube in the 256 color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorSupport {
+    // Like the graphics protocol, guessed from the environment only
+    pub fn detect() -> Self {
+        let color_term = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+
+        if color_term == "truecolor"
+            || color_term == "24bit"
+            || term.contains("direct")
+            || ["xterm-kitty", "xterm-ghostty"].contains(&term.as_str())
+            || env::var_os("WT_SESSION").is_some()
+        {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    // Nearest color the terminal can show
+    pub fn convert(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorSupport::TrueColor, color) => color,
+            (ColorSupport::Ansi256, Color::Rgb { r, g, b }) => {
+                Color::AnsiValue(nearest_ansi_256([r, g, b]))
+            }
+            (ColorSupport::Ansi16, Color::Rgb { .. } | Color::AnsiValue(_)) => {
+                nearest_ansi_16(rgb(color))
+            }
+            (_, color) => color,
+        }
+    }
+}
+
+pub fn rgb(color: Color) -> [u8; 3] {
+    match color {
+        Color::Rgb { r, g, b } => [r, g, b],
+        Color::Reset => [0, 0, 0],
+        Color::AnsiValue(value) => ansi_256_rgb(value),
+        color => ANSI_16
+            .iter()
+            .find(|(named, _)| *named == color)
+            .map_or([0, 0, 0], |(_, rgb)| *rgb),
+    }
+}
+
+fn ansi_256_rgb(value: u8) -> [u8; 3] {
+    match value {
+        0..=15 => ANSI_16[value as usize].1,
+        16..=231 => {
+            let index = value - 16;
+            [
+                CUBE_LEVELS[index as usize / 36],
+                CUBE_LEVELS[index as usize / 6 % 6],
+                CUBE_LEVELS[index as usize % 6],
+            ]
+        }
+        _ => {
+            let level = 8 + (value - 232) * 10;
+            [level, level, level]
+        }
+    }
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (*a as i32 - b as i32).pow(2) as u32)
+        .sum()
+}
+
+// Searches the cube and the grey ramp, the first 16 colors are left out as terminals change them
+fn nearest_ansi_256(color: [u8; 3]) -> u8 {
+    (16..=255)
+        .min_by_key(|value| distance(color, ansi_256_rgb(*value)))
+        .unwrap()
+}
+
+fn nearest_ansi_16(color: [u8; 3]) -> Color {
+    ANSI_16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(color, *rgb))
+        .unwrap()
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn true_color_unchanged() {
+        let color = Color::Rgb { r: 1, g: 2, b: 3 };
+        assert_eq!(ColorSupport::TrueColor.convert(color), color);
+    }
+
+    #[test]
+    fn ansi_256_cube_and_greys() {
+        let convert = |r, g, b| ColorSupport::Ansi256.convert(Color::Rgb { r, g, b });
+
+        assert_eq!(convert(255, 0, 0), Color::AnsiValue(196));
+        assert_eq!(convert(95, 135, 175), Color::AnsiValue(67));
+        assert_eq!(convert(100, 100, 100), Color::AnsiValue(241));
+        assert_eq!(
+            ColorSupport::Ansi256.convert(Color::DarkBlue),
+            Color::DarkBlue
+        );
+    }
+
+    #[test]
+    fn ansi_16_nearest() {
+        let convert = |r, g, b| ColorSupport::Ansi16.convert(Color::Rgb { r, g, b });
+
+        assert_eq!(convert(160, 207, 242), Color::Grey);
+        assert_eq!(convert(0, 82, 8), Color::DarkGreen);
+        assert_eq!(convert(250, 10, 10), Color::Red);
+        assert_eq!(
+            ColorSupport::Ansi16.convert(Color::AnsiValue(196)),
+            Color::Red
+        );
+    }
+}
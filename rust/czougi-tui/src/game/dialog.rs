@@ -0,0 +1,102 @@
+use super::{input::MouseState, render_target::RenderTarget};
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    Result,
+};
+
+pub const DIALOG_WIDTH: u16 = 50;
+pub const DIALOG_HEIGHT: u16 = 7;
+
+pub struct Dialog {
+    message: String,
+    buttons: Vec<&'static str>,
+    width: u16, // Grows beyond DIALOG_WIDTH when the buttons don't fit
+}
+
+impl Dialog {
+    pub fn new(message: &str, buttons: &[&'static str]) -> Self {
+        // Every button gets an equally wide slot, with some space around the widest one
+        let widest_button = buttons
+            .iter()
+            .map(|text| text.chars().count())
+            .max()
+            .unwrap_or(0);
+        let buttons_width = buttons.len() * (widest_button + 4);
+
+        Dialog {
+            message: message.chars().take(DIALOG_WIDTH as usize - 4).collect(),
+            buttons: buttons.to_vec(),
+            width: DIALOG_WIDTH.max(buttons_width as u16 + 4),
+        }
+    }
+
+    // Draws the dialog centered in the given area and returns the index of the clicked button
+    pub fn draw(
+        &self,
+        target: &mut impl RenderTarget,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        mouse_state: &MouseState,
+    ) -> Result<Option<usize>> {
+        let x = x + width.saturating_sub(self.width) / 2;
+        let y = y + height.saturating_sub(DIALOG_HEIGHT) / 2;
+        let inner_width = self.width as usize - 2;
+
+        queue!(
+            target,
+            SetBackgroundColor(Color::Black),
+            SetForegroundColor(Color::White),
+            cursor::MoveTo(x, y),
+            Print(format!("┌{}┐", "─".repeat(inner_width))),
+        )?;
+
+        for row in y + 1..y + DIALOG_HEIGHT - 1 {
+            queue!(
+                target,
+                cursor::MoveTo(x, row),
+                Print(format!("│{}│", " ".repeat(inner_width))),
+            )?;
+        }
+
+        queue!(
+            target,
+            cursor::MoveTo(x, y + DIALOG_HEIGHT - 1),
+            Print(format!("└{}┘", "─".repeat(inner_width))),
+            cursor::MoveTo(
+                x + (self.width - self.message.chars().count() as u16) / 2,
+                y + 2
+            ),
+            Print(&self.message),
+        )?;
+
+        let mut clicked_button = None;
+        let slot_width = self.width / self.buttons.len() as u16;
+
+        for (i, text) in self.buttons.iter().enumerate() {
+            let text = format!(" {} ", text);
+            let text_width = text.chars().count() as u16;
+            let button_x = x + slot_width * i as u16 + (slot_width - text_width) / 2;
+            let button_y = y + 4;
+
+            if mouse_state.is_hovered(button_x, button_y, text_width - 1, 0) {
+                queue!(target, SetAttribute(Attribute::Reverse))?;
+            }
+
+            queue!(
+                target,
+                cursor::MoveTo(button_x, button_y),
+                Print(text),
+                SetAttribute(Attribute::NoReverse),
+            )?;
+
+            if mouse_state.is_clicked(button_x, button_y, text_width - 1, 0) {
+                clicked_button = Some(i);
+            }
+        }
+
+        Ok(clicked_button)
+    }
+}
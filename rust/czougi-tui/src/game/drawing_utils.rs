@@ -1,30 +1,54 @@
-use std::{io::Stdout, slice::Iter};
+use super::{input::MouseState, render_target::RenderTarget};
+use std::{fmt::Display, ops::Range, slice::Iter};
 
 use crossterm::{
     cursor, queue,
-    style::{Color, Print, SetBackgroundColor},
+    style::{Color, Print, SetBackgroundColor, SetForegroundColor},
     Result,
 };
 
-pub fn draw_multi_line_text(
-    stdout: &mut Stdout,
-    text_iter: Iter<&str>,
+pub fn draw_multi_line_text<T: Display>(
+    target: &mut impl RenderTarget,
+    text_iter: Iter<T>,
     x: u16,
     y: u16,
 ) -> Result<()> {
     for (i, line) in text_iter.enumerate() {
-        queue!(stdout, cursor::MoveTo(x, y + i as u16), Print(line))?;
+        queue!(target, cursor::MoveTo(x, y + i as u16), Print(line))?;
     }
 
     Ok(())
 }
 
-pub fn draw_background(stdout: &mut Stdout, x: u16, y: u16) -> Result<()> {
-    queue!(stdout, SetBackgroundColor(Color::Black))?;
+// Draws only the given columns and rows of the text, counted in chars, starting at x and y
+pub fn draw_clipped_lines(
+    target: &mut impl RenderTarget,
+    lines: &[impl AsRef<str>],
+    x: u16,
+    y: u16,
+    columns: Range<u16>,
+    rows: Range<u16>,
+) -> Result<()> {
+    for (i, row) in rows.enumerate() {
+        let line: String = lines[row as usize]
+            .as_ref()
+            .chars()
+            .skip(columns.start as usize)
+            .take(columns.len())
+            .collect();
+
+        queue!(target, cursor::MoveTo(x, y + i as u16), Print(line))?;
+    }
+
+    Ok(())
+}
+
+pub fn draw_background(target: &mut impl RenderTarget, x: u16, y: u16) -> Result<()> {
+    queue!(target, SetBackgroundColor(Color::Black))?;
 
     for row in y..50 + y {
         queue!(
-            stdout,
+            target,
             cursor::MoveTo(x, row),
             Print("                                                                                                                          "),
         )?;
@@ -32,3 +56,102 @@ pub fn draw_background(stdout: &mut Stdout, x: u16, y: u16) -> Result<()> {
 
     Ok(())
 }
+
+// Leads back to the menu from the screens opened from it
+pub fn draw_back_arrow(target: &mut impl RenderTarget, x: u16, y: u16) -> Result<()> {
+    queue!(
+        target,
+        cursor::MoveTo(x, y),
+        SetBackgroundColor(Color::Black),
+        SetForegroundColor(Color::Red),
+        Print("◄--"),
+    )?;
+
+    Ok(())
+}
+
+// Splits text into lines no longer than width, breaking on spaces where possible
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+
+        while word.len() > width {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+
+        let line_length = line.chars().count();
+        if line_length > 0 && line_length + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.extend(word);
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+// Buttons of a setting in one row, each as wide as its text with a space on both sides
+fn choice_buttons<'a>(x: u16, choices: &'a [&str]) -> impl Iterator<Item = (u16, String)> + 'a {
+    choices.iter().scan(x, |button_x, choice| {
+        let text = format!(" {} ", choice);
+        let position = *button_x;
+        *button_x += text.chars().count() as u16 + 1;
+
+        Some((position, text))
+    })
+}
+
+// Label of a setting with the row of its choices below it
+pub fn draw_choice(
+    target: &mut impl RenderTarget,
+    x: u16,
+    y: u16,
+    label: &str,
+    choices: &[&str],
+    selected: Option<usize>,
+) -> Result<()> {
+    queue!(
+        target,
+        SetBackgroundColor(Color::Black),
+        SetForegroundColor(Color::White),
+        cursor::MoveTo(x, y),
+        Print(label),
+    )?;
+
+    for (i, (button_x, text)) in choice_buttons(x, choices).enumerate() {
+        let (background, foreground) = if selected == Some(i) {
+            (Color::White, Color::Black)
+        } else {
+            (Color::DarkGrey, Color::White)
+        };
+
+        queue!(
+            target,
+            SetBackgroundColor(background),
+            SetForegroundColor(foreground),
+            cursor::MoveTo(button_x, y + 2),
+            Print(text),
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn clicked_choice(mouse_state: &MouseState, x: u16, y: u16, choices: &[&str]) -> Option<usize> {
+    choice_buttons(x, choices).position(|(button_x, text)| {
+        mouse_state.is_clicked(button_x, y + 2, text.chars().count() as u16 - 1, 0)
+    })
+}
@@ -0,0 +1,583 @@
+use std::{
+    io::{self, Write},
+    mem,
+};
+
+use crossterm::{
+    cursor, queue,
+    style::{
+        Attribute, Attributes, Color, Colored, Print, SetAttribute, SetBackgroundColor,
+        SetForegroundColor,
+    },
+    Result,
+};
+
+use super::{colors::ColorSupport, glyphs::ascii_fallback};
+use unicode_width::UnicodeWidthChar;
+
+const ESCAPE: u8 = 0x1b;
+// Second column of a glyph two columns wide, covered by the glyph of the previous cell
+const WIDE_GLYPH_CONTINUATION: char = '\0';
+
+#[derive(Copy, Clone, PartialEq)]
+pub struct Cell {
+    pub glyph: char,
+    pub foreground: Color,
+    pub background: Color,
+    pub attributes: Attributes,
+}
+
+impl Cell {
+    fn blank(background: Color) -> Self {
+        Cell {
+            glyph: ' ',
+            foreground: Color::Reset,
+            background,
+            attributes: Attributes::default(),
+        }
+    }
+
+    // Never equal to a drawn cell, so that the cell is sent to the terminal on the next render
+    fn unknown() -> Self {
+        Cell {
+            glyph: '\u{ffff}',
+            ..Cell::blank(Color::Reset)
+        }
+    }
+}
+
+// Screen contents drawn in memory. Commands are queued on it like on the terminal and the cells
+// changed since the last frame are sent to the terminal at once by `render`, so modes draw the
+// whole screen on every frame and only the differences reach the terminal.
+pub struct Frame {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+    rendered_cells: Vec<Cell>, // As shown by the terminal
+    cursor: (u16, u16),
+    foreground: Color,
+    background: Color,
+    attributes: Attributes,
+    unparsed: Vec<u8>, // Beginning of an escape sequence or a character split between writes
+    images: Vec<(u16, u16, Vec<u8>)>, // Graphics protocol sequences passed through as they are
+    rendered_images: Vec<(u16, u16, Vec<u8>)>,
+}
+
+impl Frame {
+    pub fn new() -> Self {
+        Frame {
+            width: 0,
+            height: 0,
+            cells: vec![],
+            rendered_cells: vec![],
+            cursor: (0, 0),
+            foreground: Color::Reset,
+            background: Color::Reset,
+            attributes: Attributes::default(),
+            unparsed: vec![],
+            images: vec![],
+            rendered_images: vec![],
+        }
+    }
+
+    // Contents of the terminal are unknown after resizing, so everything is sent again
+    pub fn resize(&mut self, width: u16, height: u16) {
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+
+        let size = width as usize * height as usize;
+        self.width = width;
+        self.height = height;
+        self.cells = vec![Cell::blank(Color::Reset); size];
+        self.rendered_cells = vec![Cell::unknown(); size];
+    }
+
+    // Everything is sent to the terminal on the next render
+    pub fn invalidate(&mut self) {
+        self.rendered_cells.fill(Cell::unknown());
+        self.rendered_images.clear();
+    }
+
+    // Sends the changed cells to the terminal, in the colors and glyphs it supports
+    pub fn render(
+        &mut self,
+        terminal: &mut impl Write,
+        color_support: ColorSupport,
+        ascii_only: bool,
+    ) -> Result<()> {
+        let mut pen: Option<Cell> = None;
+        let mut next_position = None;
+
+        for (i, (cell, rendered_cell)) in self.cells.iter().zip(&self.rendered_cells).enumerate() {
+            if cell == rendered_cell {
+                continue;
+            }
+
+            // Shown by the wide glyph before it, unless it is replaced with a narrow one
+            if cell.glyph == WIDE_GLYPH_CONTINUATION && !ascii_only {
+                continue;
+            }
+
+            let position = (
+                (i % self.width as usize) as u16,
+                (i / self.width as usize) as u16,
+            );
+
+            if next_position != Some(position) {
+                queue!(terminal, cursor::MoveTo(position.0, position.1))?;
+            }
+
+            let attributes_changed = pen.is_none_or(|pen| pen.attributes != cell.attributes);
+
+            // Attributes can only be turned off all at once, together with the colors
+            if attributes_changed {
+                queue!(terminal, SetAttribute(Attribute::Reset))?;
+
+                for attribute in Attribute::iterator().filter(|a| cell.attributes.has(*a)) {
+                    queue!(terminal, SetAttribute(attribute))?;
+                }
+            }
+
+            if attributes_changed || pen.is_none_or(|pen| pen.foreground != cell.foreground) {
+                queue!(
+                    terminal,
+                    SetForegroundColor(color_support.convert(cell.foreground))
+                )?;
+            }
+
+            if attributes_changed || pen.is_none_or(|pen| pen.background != cell.background) {
+                queue!(
+                    terminal,
+                    SetBackgroundColor(color_support.convert(cell.background))
+                )?;
+            }
+
+            let (glyph, width) = match cell.glyph {
+                // ASCII replacements are a single column wide
+                WIDE_GLYPH_CONTINUATION => (' ', 1),
+                glyph if ascii_only => (ascii_fallback(glyph), 1),
+                glyph => (glyph, glyph.width().unwrap_or(1) as u16),
+            };
+
+            queue!(terminal, Print(glyph))?;
+            pen = Some(*cell);
+            next_position = Some((position.0 + width, position.1));
+        }
+
+        // Text sent over an image may have covered a part of it
+        if next_position.is_some() || self.images != self.rendered_images {
+            for (x, y, image) in &self.images {
+                queue!(terminal, cursor::MoveTo(*x, *y))?;
+                terminal.write_all(image)?;
+            }
+        }
+
+        self.rendered_images = mem::take(&mut self.images);
+        self.rendered_cells.copy_from_slice(&self.cells);
+        terminal.flush()?;
+        Ok(())
+    }
+
+    fn parse(&mut self) {
+        let mut unparsed = mem::take(&mut self.unparsed);
+        let mut start = 0;
+
+        while start < unparsed.len() {
+            let parsed = if unparsed[start] == ESCAPE {
+                self.parse_escape_sequence(&unparsed[start..])
+            } else {
+                self.parse_text(&unparsed[start..])
+            };
+
+            match parsed {
+                Some(length) => start += length,
+                // Rest of the sequence comes with the next write
+                None => break,
+            }
+        }
+
+        unparsed.drain(..start);
+        self.unparsed = unparsed;
+    }
+
+    // Returns the length of the sequence, if it is complete
+    fn parse_escape_sequence(&mut self, bytes: &[u8]) -> Option<usize> {
+        match bytes.get(1)? {
+            b'[' => {
+                let end = 2 + bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b))?;
+                let parameters = String::from_utf8_lossy(&bytes[2..end]);
+                self.handle_control_sequence(&parameters, bytes[end]);
+                Some(end + 1)
+            }
+            // Sixel and Kitty images, ended by the string terminator
+            b'P' | b'_' => {
+                let end = bytes.windows(2).position(|w| w == [ESCAPE, b'\\'])? + 2;
+                let (x, y) = self.cursor;
+                self.images.push((x, y, bytes[..end].to_vec()));
+                Some(end)
+            }
+            _ => Some(2),
+        }
+    }
+
+    fn handle_control_sequence(&mut self, parameters: &str, command: u8) {
+        match command {
+            // Position counted from one
+            b'H' => {
+                let mut numbers = parameters.split(';').map(|n| n.parse::<u16>().unwrap_or(1));
+                let row = numbers.next().unwrap_or(1);
+                let column = numbers.next().unwrap_or(1);
+                self.cursor = (column.saturating_sub(1), row.saturating_sub(1));
+            }
+            b'm' => match Colored::parse_ansi(parameters) {
+                Some(Colored::ForegroundColor(color)) => self.foreground = color,
+                Some(Colored::BackgroundColor(color)) => self.background = color,
+                None => self.set_attribute(parameters.parse().unwrap_or(0)),
+            },
+            // Erased cells take the current background color, like in terminals
+            b'J' if parameters == "2" => {
+                let blank = Cell::blank(self.background);
+                self.cells.iter_mut().for_each(|cell| *cell = blank);
+            }
+            // Showing and hiding the cursor is not a part of the frame
+            _ => {}
+        }
+    }
+
+    fn set_attribute(&mut self, sgr: i16) {
+        let attribute = match Attribute::iterator().find(|a| a.sgr() == sgr) {
+            Some(attribute) => attribute,
+            None => return,
+        };
+
+        match attribute {
+            Attribute::Reset => {
+                self.attributes = Attributes::default();
+                self.foreground = Color::Reset;
+                self.background = Color::Reset;
+            }
+            Attribute::NoBold => {
+                self.attributes.unset(Attribute::Bold);
+                self.attributes.unset(Attribute::Dim);
+            }
+            Attribute::NoUnderline => self.attributes.unset(Attribute::Underlined),
+            Attribute::NoReverse => self.attributes.unset(Attribute::Reverse),
+            Attribute::NoItalic => self.attributes.unset(Attribute::Italic),
+            Attribute::NotFramedOrEncircled => {
+                self.attributes.unset(Attribute::Framed);
+                self.attributes.unset(Attribute::Encircled);
+            }
+            attribute => self.attributes.set(attribute),
+        }
+    }
+
+    // Returns the length of the text up to the next escape sequence, if it is complete
+    fn parse_text(&mut self, bytes: &[u8]) -> Option<usize> {
+        let length = bytes
+            .iter()
+            .position(|b| *b == ESCAPE)
+            .unwrap_or(bytes.len());
+
+        let (text, length) = match std::str::from_utf8(&bytes[..length]) {
+            Ok(text) => (text, length),
+            // Character split between writes
+            Err(error) if error.error_len().is_none() && error.valid_up_to() > 0 => {
+                let valid = error.valid_up_to();
+                (std::str::from_utf8(&bytes[..valid]).unwrap(), valid)
+            }
+            Err(error) if error.error_len().is_none() => return None,
+            Err(error) => {
+                let valid = error.valid_up_to();
+                let invalid = valid + error.error_len().unwrap_or(1);
+                self.print(std::str::from_utf8(&bytes[..valid]).unwrap());
+                self.print("\u{fffd}");
+                return Some(invalid);
+            }
+        };
+
+        self.print(text);
+        Some(length)
+    }
+
+    // Text going past the right edge is cut off. Glyphs two columns wide take two cells and
+    // combining characters are left out.
+    fn print(&mut self, text: &str) {
+        for glyph in text.chars() {
+            let width = match glyph.width() {
+                Some(0) => continue,
+                Some(width) => width as u16,
+                None => 1,
+            };
+
+            let cell = Cell {
+                glyph,
+                foreground: self.foreground,
+                background: self.background,
+                attributes: self.attributes,
+            };
+            let (x, y) = self.cursor;
+
+            self.set_cell(x, y, cell);
+            if width == 2 {
+                self.set_cell(
+                    x.saturating_add(1),
+                    y,
+                    Cell {
+                        glyph: WIDE_GLYPH_CONTINUATION,
+                        ..cell
+                    },
+                );
+            }
+
+            self.cursor.0 = x.saturating_add(width);
+        }
+    }
+
+    // Wide glyphs partly overwritten are replaced with spaces, as terminals do
+    fn set_cell(&mut self, x: u16, y: u16, cell: Cell) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let i = y as usize * self.width as usize + x as usize;
+
+        if cell.glyph != WIDE_GLYPH_CONTINUATION
+            && self.cells[i].glyph == WIDE_GLYPH_CONTINUATION
+            && x > 0
+        {
+            self.cells[i - 1].glyph = ' ';
+        }
+
+        if x + 1 < self.width && self.cells[i + 1].glyph == WIDE_GLYPH_CONTINUATION {
+            self.cells[i + 1].glyph = ' ';
+        }
+
+        self.cells[i] = cell;
+    }
+}
+
+#[cfg(test)]
+impl Frame {
+    // Text of the frame followed by the style of every cell, each distinct style named with a
+    // single character and described in between
+    pub fn snapshot(&self) -> String {
+        const STYLE_NAMES: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+        let mut styles: Vec<(Color, Color, Attributes)> = vec![];
+        let mut text = String::new();
+        let mut style_rows = String::new();
+
+        for row in self.cells.chunks(self.width.max(1) as usize) {
+            let line: String = row
+                .iter()
+                .map(|cell| cell.glyph)
+                .filter(|glyph| *glyph != WIDE_GLYPH_CONTINUATION)
+                .collect();
+            text.push_str(line.trim_end());
+            text.push('\n');
+
+            for cell in row {
+                let style = (cell.foreground, cell.background, cell.attributes);
+                let index = match styles.iter().position(|s| *s == style) {
+                    Some(index) => index,
+                    None => {
+                        styles.push(style);
+                        styles.len() - 1
+                    }
+                };
+                style_rows.push(STYLE_NAMES.chars().nth(index).expect("Too many styles"));
+            }
+            style_rows.push('\n');
+        }
+
+        let legend: String = styles
+            .iter()
+            .zip(STYLE_NAMES.chars())
+            .map(|((foreground, background, attributes), name)| {
+                let attributes: Vec<String> = Attribute::iterator()
+                    .filter(|a| attributes.has(*a))
+                    .map(|a| format!(" {:?}", a))
+                    .collect();
+                format!(
+                    "{}: {:?} on {:?}{}\n",
+                    name,
+                    foreground,
+                    background,
+                    attributes.concat()
+                )
+            })
+            .collect();
+
+        format!("{}\n{}\n{}", text, legend, style_rows)
+    }
+}
+
+impl Write for Frame {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.unparsed.extend_from_slice(buf);
+        self.parse();
+        Ok(buf.len())
+    }
+
+    // Frame reaches the terminal only with `render`
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::terminal::{Clear, ClearType};
+
+    fn frame(width: u16, height: u16) -> Frame {
+        let mut frame = Frame::new();
+        frame.resize(width, height);
+        frame
+    }
+
+    fn rendered(frame: &mut Frame) -> String {
+        let mut output = vec![];
+        frame
+            .render(&mut output, ColorSupport::TrueColor, false)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn wide_glyphs_take_two_cells() {
+        let mut frame = frame(6, 1);
+        queue!(frame, Print("a界b")).unwrap();
+        assert_eq!(frame.snapshot().lines().next(), Some("a界b"));
+
+        // Narrow glyph over the second half of the wide one
+        queue!(frame, cursor::MoveTo(2, 0), Print("x")).unwrap();
+        assert_eq!(frame.snapshot().lines().next(), Some("a xb"));
+    }
+
+    #[test]
+    fn only_changes_rendered() {
+        let mut frame = frame(4, 1);
+        queue!(frame, Print("abcd")).unwrap();
+        assert!(rendered(&mut frame).contains("abcd"));
+
+        queue!(frame, cursor::MoveTo(0, 0), Print("abXd")).unwrap();
+        let output = rendered(&mut frame);
+        assert!(output.contains('X') && !output.contains('a'));
+
+        queue!(frame, cursor::MoveTo(0, 0), Print("abXd")).unwrap();
+        assert!(rendered(&mut frame).is_empty());
+    }
+
+    fn cell(frame: &Frame, x: u16, y: u16) -> Cell {
+        frame.cells[y as usize * frame.width as usize + x as usize]
+    }
+
+    #[test]
+    fn cursor_moved_by_position_counted_from_one() {
+        let mut frame = frame(4, 3);
+        frame.write_all(b"\x1b[2;3Hx\x1b[Hy\x1b[3Hz").unwrap();
+
+        assert!(cell(&frame, 2, 1).glyph == 'x');
+        assert!(cell(&frame, 0, 0).glyph == 'y');
+        assert!(cell(&frame, 0, 2).glyph == 'z');
+    }
+
+    #[test]
+    fn colors_and_attributes_kept_until_reset() {
+        let mut frame = frame(3, 1);
+        queue!(
+            frame,
+            SetForegroundColor(Color::Red),
+            SetBackgroundColor(Color::Rgb { r: 1, g: 2, b: 3 }),
+            SetAttribute(Attribute::Bold),
+            Print("a"),
+            SetAttribute(Attribute::NoBold),
+            Print("b"),
+            SetAttribute(Attribute::Reset),
+            Print("c"),
+        )
+        .unwrap();
+
+        let (a, b, c) = (cell(&frame, 0, 0), cell(&frame, 1, 0), cell(&frame, 2, 0));
+        assert!(a.foreground == Color::Red && a.background == Color::Rgb { r: 1, g: 2, b: 3 });
+        assert!(a.attributes.has(Attribute::Bold));
+        assert!(b.foreground == Color::Red && !b.attributes.has(Attribute::Bold));
+        assert!(c.foreground == Color::Reset && c.background == Color::Reset);
+    }
+
+    #[test]
+    fn clear_fills_screen_with_background() {
+        let mut frame = frame(2, 2);
+        queue!(
+            frame,
+            Print("ab"),
+            SetBackgroundColor(Color::Green),
+            Clear(ClearType::All)
+        )
+        .unwrap();
+
+        assert!(frame
+            .cells
+            .iter()
+            .all(|cell| *cell == Cell::blank(Color::Green)));
+    }
+
+    #[test]
+    fn sequence_split_between_writes() {
+        let mut frame = frame(4, 2);
+        frame.write_all(b"\x1b[2;").unwrap();
+        frame.write_all(b"2H\xe7\x95").unwrap();
+        frame.write_all(b"\x8c").unwrap();
+
+        assert!(cell(&frame, 1, 1).glyph == '界');
+    }
+
+    #[test]
+    fn wide_glyphs_rendered_once() {
+        let mut frame = frame(4, 1);
+        queue!(frame, Print("界ab")).unwrap();
+        let output = rendered(&mut frame);
+        assert!(output.contains("界ab") && !output.contains('\0'));
+
+        // Replaced with a narrow glyph followed by a space
+        frame.invalidate();
+        queue!(frame, cursor::MoveTo(0, 0), Print("界ab")).unwrap();
+        let mut output = vec![];
+        frame
+            .render(&mut output, ColorSupport::TrueColor, true)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains('界') && output.contains(" ab"));
+    }
+
+    #[test]
+    fn images_passed_through() {
+        let sixel = b"\x1bPq#0;2;0;0;0#0~~-\x1b\\";
+        let kitty = b"\x1b_Gf=100,a=T;AAAA\x1b\\";
+        let mut frame = frame(8, 4);
+
+        queue!(frame, cursor::MoveTo(1, 2)).unwrap();
+        frame.write_all(sixel).unwrap();
+        queue!(frame, cursor::MoveTo(4, 0)).unwrap();
+        frame.write_all(&kitty[..6]).unwrap();
+        frame.write_all(&kitty[6..]).unwrap();
+
+        // Images take no cells of the frame
+        assert!(frame.cells.iter().all(|cell| cell.glyph == ' '));
+        let output = rendered(&mut frame);
+        let sixel_at = output.find("\x1b[3;2H\x1bPq").unwrap();
+        let kitty_at = output.find("\x1b[1;5H\x1b_G").unwrap();
+        assert!(sixel_at < kitty_at);
+        assert!(output.contains(std::str::from_utf8(sixel).unwrap()));
+        assert!(output.contains(std::str::from_utf8(kitty).unwrap()));
+
+        // Same images on an unchanged screen are not sent again
+        queue!(frame, cursor::MoveTo(1, 2)).unwrap();
+        frame.write_all(sixel).unwrap();
+        queue!(frame, cursor::MoveTo(4, 0)).unwrap();
+        frame.write_all(kitty).unwrap();
+        assert!(rendered(&mut frame).is_empty());
+    }
+}
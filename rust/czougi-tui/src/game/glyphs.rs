@@ -0,0 +1,34 @@
+use std::env;
+
+// Terminals known to show only ASCII, guessed from the environment like the color support
+pub fn detect_ascii_only() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+
+    ["dumb", "vt52", "vt100", "vt102", "vt220", "ansi"].contains(&term.as_str())
+}
+
+// Closest ASCII character for the glyphs used by the menus, frames and the sidebar
+pub fn ascii_fallback(glyph: char) -> char {
+    match glyph {
+        ' '..='~' => glyph,
+        '─' | '═' => '-',
+        '│' => '|',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => '+',
+        '█' | '▐' | '▌' => '#',
+        '▀' => '"',
+        '▄' => '_',
+        '░' => ':',
+        '▚' => '%',
+        '▲' => '^',
+        '▼' => 'v',
+        '◄' => '<',
+        '►' => '>',
+        '✔' => 'x',
+        '⚠' => '!',
+        '•' | '●' => '*',
+        // Any dot of a braille character
+        '\u{2801}'..='\u{28ff}' => '.',
+        '\u{2800}' => ' ',
+        _ => '?',
+    }
+}
@@ -1,6 +1,6 @@
 use super::options::PlayerKeybindings;
 use crossterm::{
-    event::{read, Event, MouseButton, MouseEvent, MouseEventKind},
+    event::{read, Event, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
     terminal::size,
     Result,
 };
@@ -11,6 +11,7 @@ use std::sync::{mpsc, Arc, Mutex};
 pub struct Input {
     mouse_state: Arc<Mutex<MouseState>>,
     window_state: Arc<Mutex<WindowState>>,
+    key_events: Arc<Mutex<Vec<KeyEvent>>>,
     device_state: DeviceState,
     stoppper_tx: mpsc::Sender<()>,
 }
@@ -22,14 +23,16 @@ impl Input {
         let input = Input {
             mouse_state: Arc::new(Mutex::new(MouseState::new())),
             window_state: Arc::new(Mutex::new(WindowState::new()?)),
+            key_events: Arc::new(Mutex::new(Vec::new())),
             device_state: DeviceState::new(),
             stoppper_tx,
         };
 
         let mouse_state = input.mouse_state.clone();
         let window_state = input.window_state.clone();
+        let key_events = input.key_events.clone();
 
-        // Thread updating mouse and window state
+        // Thread updating mouse, window and text input state
         std::thread::spawn(move || loop {
             if let Ok(()) = stoppper_rx.try_recv() {
                 break;
@@ -44,7 +47,10 @@ impl Input {
                     let mut window_state = window_state.lock().unwrap();
                     window_state.update(width, height);
                 }
-                _ => {}
+                Event::Key(key_event) => {
+                    let mut key_events = key_events.lock().unwrap();
+                    key_events.push(key_event);
+                }
             }
         });
 
@@ -56,6 +62,7 @@ impl Input {
             mouse_state: self.mouse_state.lock().unwrap().get_state(),
             window_state: self.window_state.lock().unwrap().get_state(),
             keyboard_state: self.device_state.get_keys(),
+            key_events: self.key_events.lock().unwrap().drain(..).collect(),
         }
     }
 }
@@ -69,9 +76,12 @@ impl Drop for Input {
 pub struct InputState {
     pub mouse_state: MouseState,
     pub keyboard_state: Vec<Keycode>,
+    // Key events received from the terminal since the last frame, used for text entry
+    pub key_events: Vec<KeyEvent>,
     pub window_state: WindowState,
 }
 
+#[allow(dead_code)]
 pub struct PlayerKeysState {
     pub up: bool,
     pub down: bool,
@@ -80,8 +90,9 @@ pub struct PlayerKeysState {
     pub shoot: bool,
 }
 
+#[allow(dead_code)]
 impl PlayerKeysState {
-    pub fn new(keys: &Vec<Keycode>, keybindings: &PlayerKeybindings) -> Self {
+    pub fn new(keys: &[Keycode], keybindings: &PlayerKeybindings) -> Self {
         PlayerKeysState {
             up: keys.contains(&keybindings.up),
             down: keys.contains(&keybindings.down),
@@ -92,14 +103,14 @@ impl PlayerKeysState {
     }
 
     pub fn get_players_keys_state(
-        keys: &Vec<Keycode>,
+        keys: &[Keycode],
         keybindings: &[PlayerKeybindings; 4],
     ) -> [PlayerKeysState; 4] {
         [
-            PlayerKeysState::new(&keys, &keybindings[0]),
-            PlayerKeysState::new(&keys, &keybindings[1]),
-            PlayerKeysState::new(&keys, &keybindings[2]),
-            PlayerKeysState::new(&keys, &keybindings[3]),
+            PlayerKeysState::new(keys, &keybindings[0]),
+            PlayerKeysState::new(keys, &keybindings[1]),
+            PlayerKeysState::new(keys, &keybindings[2]),
+            PlayerKeysState::new(keys, &keybindings[3]),
         ]
     }
 }
@@ -119,11 +130,49 @@ pub enum ButtonState {
     GettingReleased,
 }
 
+impl ButtonState {
+    fn press(&mut self) {
+        match self {
+            ButtonState::GettingPressed => *self = ButtonState::Pressed,
+            ButtonState::GettingReleased => *self = ButtonState::GettingPressed,
+            ButtonState::Released => *self = ButtonState::GettingPressed,
+            _ => {}
+        };
+    }
+
+    fn release(&mut self) {
+        match self {
+            ButtonState::GettingPressed => *self = ButtonState::GettingReleased,
+            ButtonState::GettingReleased => *self = ButtonState::Released,
+            ButtonState::Pressed => *self = ButtonState::GettingReleased,
+            _ => {}
+        };
+    }
+
+    // Dragging means the button is held, even if the press event was missed
+    fn drag(&mut self) {
+        if matches!(self, ButtonState::Released) {
+            *self = ButtonState::GettingPressed;
+        }
+    }
+
+    // Moves transitional states to the stable ones once they have been observed
+    fn settle(&mut self) {
+        match self {
+            ButtonState::GettingPressed => *self = ButtonState::Pressed,
+            ButtonState::GettingReleased => *self = ButtonState::Released,
+            _ => {}
+        };
+    }
+}
+
 #[derive(Clone)]
 pub struct MouseState {
     pub column: u16,
     pub row: u16,
     pub left_button: ButtonState,
+    pub right_button: ButtonState,
+    pub middle_button: ButtonState,
     pub scroll: ScrollState,
 }
 
@@ -133,6 +182,8 @@ impl MouseState {
             column: 1,
             row: 1,
             left_button: ButtonState::Released,
+            right_button: ButtonState::Released,
+            middle_button: ButtonState::Released,
             scroll: ScrollState::None,
         }
     }
@@ -142,43 +193,36 @@ impl MouseState {
         self.row = mouse_event.row;
 
         match mouse_event.kind {
-            MouseEventKind::Up(MouseButton::Left) => {
-                match self.left_button {
-                    ButtonState::GettingPressed => self.left_button = ButtonState::GettingReleased,
-                    ButtonState::GettingReleased => self.left_button = ButtonState::Released,
-                    ButtonState::Pressed => self.left_button = ButtonState::GettingReleased,
-                    _ => {}
-                };
-            }
-            MouseEventKind::Down(MouseButton::Left) => {
-                match self.left_button {
-                    ButtonState::GettingPressed => self.left_button = ButtonState::Pressed,
-                    ButtonState::GettingReleased => self.left_button = ButtonState::GettingPressed,
-                    ButtonState::Released => self.left_button = ButtonState::GettingPressed,
-                    _ => {}
-                };
-            }
+            MouseEventKind::Up(button) => self.button_mut(button).release(),
+            MouseEventKind::Down(button) => self.button_mut(button).press(),
+            MouseEventKind::Drag(button) => self.button_mut(button).drag(),
             MouseEventKind::ScrollUp => {
                 self.scroll = ScrollState::Up;
             }
             MouseEventKind::ScrollDown => {
                 self.scroll = ScrollState::Down;
             }
-            _ => {}
+            MouseEventKind::Moved => {}
         }
     }
 
     pub fn get_state(&mut self) -> MouseState {
         let state = self.clone();
         self.scroll = ScrollState::None;
-        match self.left_button {
-            ButtonState::GettingPressed => self.left_button = ButtonState::Pressed,
-            ButtonState::GettingReleased => self.left_button = ButtonState::Released,
-            _ => {}
-        };
+        self.left_button.settle();
+        self.right_button.settle();
+        self.middle_button.settle();
         state
     }
 
+    fn button_mut(&mut self, button: MouseButton) -> &mut ButtonState {
+        match button {
+            MouseButton::Left => &mut self.left_button,
+            MouseButton::Right => &mut self.right_button,
+            MouseButton::Middle => &mut self.middle_button,
+        }
+    }
+
     pub fn is_hovered(&self, x: u16, y: u16, width: u16, height: u16) -> bool {
         self.column >= x && self.column <= x + width && self.row >= y && self.row <= y + height
     }
@@ -0,0 +1,151 @@
+use super::{
+    block::{BlockType, BlockVariant},
+    tank::{Direction, TANK_SIZE},
+};
+use crate::game::{render_target::RenderTarget, theme::Theme};
+use crossterm::{
+    cursor, queue,
+    style::{Print, SetBackgroundColor, SetForegroundColor},
+    Result,
+};
+
+// Every cell is drawn with two braille characters of 2x4 dots each
+pub const DOTS_PER_CELL: u16 = 4;
+
+const BRAILLE_BASE: u32 = 0x2800;
+
+// Bits of the dots in a braille character, by row and column
+const DOT_BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+// Patterns of whole 2x2 tiles, one byte per row of dots with the leftmost dot in the highest bit
+const BRICK_PATTERN: [u8; 8] = [
+    0b11111111, 0b00010000, 0b00010000, 0b00010000, 0b11111111, 0b10000000, 0b10000000, 0b10000000,
+];
+
+const CONCRETE_PATTERN: [u8; 8] = [
+    0b11111111, 0b10000001, 0b10000001, 0b10011001, 0b10011001, 0b10000001, 0b10000001, 0b11111111,
+];
+
+const WATER_PATTERN: [u8; 8] = [
+    0b00000000, 0b01100000, 0b10010001, 0b00001110, 0b00000000, 0b00000110, 0b10001001, 0b01110000,
+];
+
+const LEAVES_PATTERN: [u8; 8] = [
+    0b10100100, 0b01001010, 0b10010101, 0b00101000, 0b01010010, 0b10100101, 0b01001010, 0b00100100,
+];
+
+// Tank facing up, one row of dots per element with the leftmost dot in the highest bit
+const TANK_PATTERN: [u16; 16] = [
+    0b0000000110000000,
+    0b0000000110000000,
+    0b0000000110000000,
+    0b0000000110000000,
+    0b1110000110000111,
+    0b1010111111110101,
+    0b1110111111110111,
+    0b1010110000110101,
+    0b1110110110110111,
+    0b1010110110110101,
+    0b1110110000110111,
+    0b1010111111110101,
+    0b1110111111110111,
+    0b1010111111110101,
+    0b1110000000000111,
+    0b1010000000000101,
+];
+
+// Turns rows of dots into lines of braille characters, the number of rows has to be a multiple
+// of four and the width a multiple of two
+fn braille_lines(is_set: impl Fn(u16, u16) -> bool, width: u16, height: u16) -> Vec<String> {
+    (0..height / 4)
+        .map(|line| {
+            (0..width / 2)
+                .map(|character| {
+                    let mut code = BRAILLE_BASE;
+
+                    for (row, bits) in DOT_BITS.iter().enumerate() {
+                        for (column, bit) in bits.iter().enumerate() {
+                            if is_set(character * 2 + column as u16, line * 4 + row as u16) {
+                                code |= bit;
+                            }
+                        }
+                    }
+
+                    char::from_u32(code).unwrap()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn block_pattern(block_type: BlockType) -> [u8; 8] {
+    match block_type {
+        BlockType::Brick => BRICK_PATTERN,
+        BlockType::Concrete => CONCRETE_PATTERN,
+        BlockType::Water => WATER_PATTERN,
+        BlockType::Leaves => LEAVES_PATTERN,
+    }
+}
+
+// Whether the dot of the block is set, in the quarter of the tile pattern given by the variant
+pub fn block_dot(
+    block_type: BlockType,
+    block_variant: BlockVariant,
+    column: u16,
+    row: u16,
+) -> bool {
+    let (left, top) = match block_variant {
+        BlockVariant::LeftTop => (0, 0),
+        BlockVariant::RightTop => (DOTS_PER_CELL, 0),
+        BlockVariant::LeftBottom => (0, DOTS_PER_CELL),
+        BlockVariant::RightBottom => (DOTS_PER_CELL, DOTS_PER_CELL),
+    };
+
+    block_pattern(block_type)[(top + row) as usize] & (0x80 >> (left + column)) != 0
+}
+
+// Whether the dot of the tank is set, with the pattern turned in the given direction
+pub fn tank_dot(direction: Direction, column: u16, row: u16) -> bool {
+    let (column, row) = match direction {
+        Direction::Up => (column, row),
+        Direction::Down => (column, 15 - row),
+        Direction::Left => (row, column),
+        Direction::Right => (row, 15 - column),
+    };
+
+    TANK_PATTERN[row as usize] & (0x8000 >> column) != 0
+}
+
+// Alternative to `block::draw_block`
+pub fn draw_block(
+    target: &mut impl RenderTarget,
+    theme: &Theme,
+    block_type: BlockType,
+    block_variant: BlockVariant,
+    x: u16,
+    y: u16,
+) -> Result<()> {
+    let lines = braille_lines(
+        |column, row| block_dot(block_type, block_variant, column, row),
+        DOTS_PER_CELL,
+        DOTS_PER_CELL,
+    );
+
+    let style = theme.block(block_type);
+
+    queue!(
+        target,
+        SetBackgroundColor(style.background),
+        SetForegroundColor(style.foreground),
+        cursor::MoveTo(x, y),
+        Print(&lines[0])
+    )?;
+    Ok(())
+}
+
+// Alternative to the tank glyphs of the theme
+pub fn tank_graphics(direction: Direction) -> Vec<String> {
+    let size = TANK_SIZE * DOTS_PER_CELL;
+
+    braille_lines(|column, row| tank_dot(direction, column, row), size, size)
+}
@@ -0,0 +1,219 @@
+use super::{image::GraphicsProtocol, Level};
+use crate::game::{render_target::RenderTarget, theme::Theme};
+use std::ops::Range;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum MapRenderer {
+    Full,                    // Every cell takes two columns and one row
+    Compact, // Every cell takes one column and half of a row, drawn with half blocks
+    Braille, // Same layout as the full renderer, but drawn with 4x4 braille dots per cell
+    Image(GraphicsProtocol), // Same layout as the full renderer, but drawn as a single image
+    Ascii,   // Same layout as the full renderer, but drawn with ASCII characters
+}
+
+// Part of the map shown on the screen, measured in cells
+#[derive(Copy, Clone)]
+pub struct Camera {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+    pub renderer: MapRenderer,
+}
+
+impl Camera {
+    pub fn new(width: u16, height: u16) -> Self {
+        Camera {
+            x: 0,
+            y: 0,
+            width,
+            height,
+            renderer: MapRenderer::Full,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.x = 0;
+        self.y = 0;
+    }
+
+    // Makes the view as large as fits in the given part of the screen, but no larger than the
+    // given number of cells, and keeps it inside the map
+    pub fn fit(&mut self, columns: u16, rows: u16, max_size: u16, level: &Level) {
+        let rows_per_cell_row = match self.renderer {
+            MapRenderer::Compact => 2,
+            _ => 1,
+        };
+
+        self.width = (columns / self.cell_columns()).min(max_size);
+        self.height = (rows * rows_per_cell_row).min(max_size);
+        self.scroll(0, 0, level);
+    }
+
+    // Size of the view on the screen
+    pub fn view_columns(&self) -> u16 {
+        self.width * self.cell_columns()
+    }
+
+    pub fn view_rows(&self) -> u16 {
+        match self.renderer {
+            MapRenderer::Full
+            | MapRenderer::Braille
+            | MapRenderer::Image(_)
+            | MapRenderer::Ascii => self.height,
+            MapRenderer::Compact => self.height.div_ceil(2),
+        }
+    }
+
+    // Columns taken by a single cell, e.g. for drawing overlays over the map
+    pub fn cell_columns(&self) -> u16 {
+        match self.renderer {
+            MapRenderer::Full
+            | MapRenderer::Braille
+            | MapRenderer::Image(_)
+            | MapRenderer::Ascii => 2,
+            MapRenderer::Compact => 1,
+        }
+    }
+
+    // Shading over a whole cell, for overlays and the part of the view beyond the map
+    pub fn shade(&self) -> String {
+        let glyph = if self.renderer == MapRenderer::Ascii {
+            ":"
+        } else {
+            "░"
+        };
+        glyph.repeat(self.cell_columns() as usize)
+    }
+
+    // Screen position of the cell relative to the top left corner of the view, if it is visible
+    pub fn view_position(&self, x: u16, y: u16) -> Option<(u16, u16)> {
+        if x < self.x || x >= self.x + self.width || y < self.y || y >= self.y + self.height {
+            return None;
+        }
+
+        match self.renderer {
+            MapRenderer::Full
+            | MapRenderer::Braille
+            | MapRenderer::Image(_)
+            | MapRenderer::Ascii => Some(((x - self.x) * 2, y - self.y)),
+            MapRenderer::Compact => Some((x - self.x, (y - self.y) / 2)),
+        }
+    }
+
+    // Map cell under the given screen position relative to the top left corner of the view, the
+    // upper one of the two sharing a character in the compact renderer
+    pub fn map_position(&self, view_column: u16, view_row: u16) -> (u16, u16) {
+        match self.renderer {
+            MapRenderer::Full
+            | MapRenderer::Braille
+            | MapRenderer::Image(_)
+            | MapRenderer::Ascii => (self.x + view_column / 2, self.y + view_row),
+            MapRenderer::Compact => (self.x + view_column, self.y + view_row * 2),
+        }
+    }
+
+    // Visible columns and rows of a square object, counted in cells from its top left corner
+    pub fn visible_part(&self, x: u16, y: u16, size: u16) -> Option<(Range<u16>, Range<u16>)> {
+        let columns = self.x.max(x) - x..(self.x + self.width).min(x + size).saturating_sub(x);
+        let rows = self.y.max(y) - y..(self.y + self.height).min(y + size).saturating_sub(y);
+
+        if columns.is_empty() || rows.is_empty() {
+            None
+        } else {
+            Some((columns, rows))
+        }
+    }
+
+    // Moves the camera by the given number of cells without leaving the map, returns true if it
+    // has moved
+    pub fn scroll(&mut self, columns: i16, rows: i16, level: &Level) -> bool {
+        let max_x = level.width.saturating_sub(self.width) as i16;
+        let max_y = level.height.saturating_sub(self.height) as i16;
+        let x = (self.x as i16 + columns).clamp(0, max_x) as u16;
+        let y = (self.y as i16 + rows).clamp(0, max_y) as u16;
+
+        let moved = (x, y) != (self.x, self.y);
+        self.x = x;
+        self.y = y;
+        moved
+    }
+}
+
+// Where and how the part of the map seen through the camera is drawn
+pub struct MapView<'a, T: RenderTarget> {
+    pub target: &'a mut T,
+    pub camera: &'a Camera,
+    pub theme: &'a Theme,
+    pub horizontal_margin: u16,
+    pub vertical_margin: u16,
+}
+
+impl<T: RenderTarget> MapView<'_, T> {
+    // Position of the cell on the screen, if it is visible
+    pub fn screen_position(&self, x: u16, y: u16) -> Option<(u16, u16)> {
+        let (view_x, view_y) = self.camera.view_position(x, y)?;
+        Some((
+            self.horizontal_margin + view_x,
+            self.vertical_margin + view_y,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(width: u16, height: u16) -> Level {
+        let mut level = Level::new();
+        level.resize(width, height);
+        level
+    }
+
+    #[test]
+    fn scroll_stays_inside_the_map() {
+        let level = level(60, 40);
+        let mut camera = Camera::new(50, 50);
+
+        assert!(camera.scroll(20, 20, &level));
+        assert_eq!((camera.x, camera.y), (10, 0));
+
+        assert!(!camera.scroll(5, 5, &level));
+        assert!(camera.scroll(-30, -30, &level));
+        assert_eq!((camera.x, camera.y), (0, 0));
+    }
+
+    #[test]
+    fn fit_keeps_the_view_inside_the_map() {
+        let level = level(60, 60);
+        let mut camera = Camera::new(20, 20);
+        camera.scroll(40, 40, &level);
+
+        camera.fit(200, 100, 50, &level);
+        assert_eq!((camera.width, camera.height), (50, 50));
+        assert_eq!((camera.x, camera.y), (10, 10));
+
+        camera.renderer = MapRenderer::Compact;
+        camera.fit(30, 10, 50, &level);
+        assert_eq!((camera.width, camera.height), (30, 20));
+        assert_eq!((camera.view_columns(), camera.view_rows()), (30, 10));
+    }
+
+    #[test]
+    fn view_positions() {
+        let level = level(60, 60);
+        let mut camera = Camera::new(10, 10);
+        camera.scroll(5, 6, &level);
+
+        assert_eq!(camera.view_position(5, 6), Some((0, 0)));
+        assert_eq!(camera.view_position(14, 15), Some((18, 9)));
+        assert_eq!(camera.view_position(4, 6), None);
+        assert_eq!(camera.view_position(15, 6), None);
+        assert_eq!(camera.view_position(5, 16), None);
+        assert_eq!(camera.map_position(19, 9), (14, 15));
+
+        camera.renderer = MapRenderer::Compact;
+        assert_eq!(camera.view_position(14, 15), Some((9, 4)));
+        assert_eq!(camera.map_position(9, 4), (14, 14));
+    }
+}
@@ -0,0 +1,337 @@
+use super::{
+    block::BlockType,
+    pathfinding::{block_grid, reachable_positions},
+    tank::{Direction, Tank, TANK_SIZE},
+    tile::{Tile, TILE_SIZE},
+    validation::MIN_SPAWN_DISTANCE,
+    Level, DEFAULT_LEVEL_SIZE,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Levels are generated in whole tiles
+const TILES: u16 = DEFAULT_LEVEL_SIZE / TILE_SIZE;
+// Free tiles left around each spawn
+const SPAWN_MARGIN: u16 = 1;
+// Attempts at generating a level where everybody can reach each other, before carving paths
+const ATTEMPTS: usize = 10;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Symmetry {
+    Horizontal, // Left half mirrors the right half
+    Vertical,   // Top half mirrors the bottom half
+    Rotational, // Map looks the same after turning it by 180 degrees
+    Quadrant,   // Map is mirrored both horizontally and vertically
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 4] = [
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::Rotational,
+        Symmetry::Quadrant,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::Horizontal => "Horizontal",
+            Symmetry::Vertical => "Vertical",
+            Symmetry::Rotational => "Rotational",
+            Symmetry::Quadrant => "Quadrant",
+        }
+    }
+}
+
+pub struct GeneratorParameters {
+    pub seed: u64,
+    pub players: u8,
+    // Percentage of tiles covered by brick, concrete, water and leaves, in that order
+    pub densities: [u8; 4],
+    // More than two players always use quadrant symmetry, as every player needs an equal position
+    pub symmetry: Symmetry,
+}
+
+impl GeneratorParameters {
+    // Defaults tuned to leave most of the map open, changed in the generator screen
+    pub fn new(seed: u64, players: u8) -> Self {
+        GeneratorParameters {
+            seed,
+            players,
+            densities: [20, 6, 4, 8],
+            // Two player arenas differ in layout from seed to seed
+            symmetry: match seed % 3 {
+                0 => Symmetry::Horizontal,
+                1 => Symmetry::Vertical,
+                _ => Symmetry::Rotational,
+            },
+        }
+    }
+}
+
+pub fn generate(parameters: &GeneratorParameters) -> Level {
+    let mut random = Random::new(parameters.seed);
+    let players = parameters.players.clamp(2, 4);
+    let symmetry = if players > 2 {
+        Symmetry::Quadrant
+    } else {
+        parameters.symmetry
+    };
+
+    let mut level = Level::new();
+    level.title = String::from("Generated arena");
+    level.description = format!("Generated from seed {}", parameters.seed);
+
+    for _ in 0..ATTEMPTS {
+        level.blocks.clear();
+        place_spawns(&mut level, &mut random, symmetry, players);
+        place_blocks(&mut level, &mut random, symmetry, &parameters.densities);
+
+        if all_spawns_connected(&level) {
+            return level;
+        }
+    }
+
+    carve_paths_to_center(&mut level);
+    level
+}
+
+fn place_spawns(level: &mut Level, random: &mut Random, symmetry: Symmetry, players: u8) {
+    level.tanks = [None, None, None, None];
+
+    // Spawns stay in the first quarter, so that their counterparts are far enough
+    let max_tile = (DEFAULT_LEVEL_SIZE / 2 - TANK_SIZE - MIN_SPAWN_DISTANCE / 2) / TILE_SIZE;
+    let x = TILE_SIZE * (1 + random.below(max_tile as u64) as u16);
+    let y = TILE_SIZE * (1 + random.below(max_tile as u64) as u16);
+    let last = DEFAULT_LEVEL_SIZE - TANK_SIZE;
+
+    let positions = match symmetry {
+        Symmetry::Horizontal => vec![(x, y), (last - x, y)],
+        Symmetry::Vertical => vec![(x, y), (x, last - y)],
+        Symmetry::Rotational => vec![(x, y), (last - x, last - y)],
+        // Opposite corners go first, so that two or three players are spread evenly
+        Symmetry::Quadrant => vec![(x, y), (last - x, last - y), (last - x, y), (x, last - y)],
+    };
+
+    for (player_number, (x, y)) in positions.into_iter().take(players as usize).enumerate() {
+        let direction = if y < DEFAULT_LEVEL_SIZE / 2 {
+            Direction::Down
+        } else {
+            Direction::Up
+        };
+
+        level.tanks[player_number] = Some(Tank { x, y, direction });
+    }
+}
+
+fn place_blocks(level: &mut Level, random: &mut Random, symmetry: Symmetry, densities: &[u8; 4]) {
+    for tile_x in 0..TILES {
+        for tile_y in 0..TILES {
+            // Every group of symmetric tiles is rolled only once, at its first tile
+            let counterparts = tile_counterparts(tile_x, tile_y, symmetry);
+            if counterparts[0] != (tile_x, tile_y) {
+                continue;
+            }
+
+            let roll = random.below(100) as u16;
+            let mut threshold = 0;
+            let mut block_type = None;
+
+            for (density, candidate) in densities.iter().zip([
+                BlockType::Brick,
+                BlockType::Concrete,
+                BlockType::Water,
+                BlockType::Leaves,
+            ]) {
+                threshold += *density as u16;
+                if roll < threshold {
+                    block_type = Some(candidate);
+                    break;
+                }
+            }
+
+            if let Some(block_type) = block_type {
+                for &(x, y) in &counterparts {
+                    if !is_near_spawn(level, x, y) {
+                        level.set_tile(&Tile::full(x, y, block_type));
+                    }
+                }
+            }
+        }
+    }
+}
+
+// All tiles which have to look the same as the given one to keep the symmetry
+fn tile_counterparts(tile_x: u16, tile_y: u16, symmetry: Symmetry) -> Vec<(u16, u16)> {
+    let last = TILES - 1;
+    let mut tiles = match symmetry {
+        Symmetry::Horizontal => vec![(tile_x, tile_y), (last - tile_x, tile_y)],
+        Symmetry::Vertical => vec![(tile_x, tile_y), (tile_x, last - tile_y)],
+        Symmetry::Rotational => vec![(tile_x, tile_y), (last - tile_x, last - tile_y)],
+        Symmetry::Quadrant => vec![
+            (tile_x, tile_y),
+            (last - tile_x, tile_y),
+            (tile_x, last - tile_y),
+            (last - tile_x, last - tile_y),
+        ],
+    };
+
+    tiles.sort_unstable();
+    tiles.dedup();
+    tiles
+}
+
+fn is_near_spawn(level: &Level, tile_x: u16, tile_y: u16) -> bool {
+    let margin = SPAWN_MARGIN * TILE_SIZE;
+    let (x, y) = (tile_x * TILE_SIZE, tile_y * TILE_SIZE);
+
+    level.tanks.iter().flatten().any(|tank| {
+        x + TILE_SIZE + margin > tank.x
+            && x < tank.x + TANK_SIZE + margin
+            && y + TILE_SIZE + margin > tank.y
+            && y < tank.y + TANK_SIZE + margin
+    })
+}
+
+fn all_spawns_connected(level: &Level) -> bool {
+    let grid = block_grid(level);
+    let tanks: Vec<&Tank> = level.tanks.iter().flatten().collect();
+
+    tanks.iter().all(|tank| {
+        let reachable = reachable_positions(&grid, tank);
+        tanks
+            .iter()
+            .all(|other| reachable.contains(&(other.x, other.y)))
+    })
+}
+
+// Clears a tank-wide corridor from every spawn to the center of the map, first horizontally then
+// vertically, so mirrored spawns get mirrored corridors and the level stays symmetric
+fn carve_paths_to_center(level: &mut Level) {
+    let center = (DEFAULT_LEVEL_SIZE - TANK_SIZE) / 2;
+    let spawns: Vec<(u16, u16)> = level
+        .tanks
+        .iter()
+        .flatten()
+        .map(|tank| (tank.x, tank.y))
+        .collect();
+
+    for (x, y) in spawns {
+        let (left, right) = (x.min(center), x.max(center));
+        let (top, bottom) = (y.min(center), y.max(center));
+
+        clear(level, left, y, right + TANK_SIZE, y + TANK_SIZE);
+        clear(level, center, top, center + TANK_SIZE, bottom + TANK_SIZE);
+    }
+}
+
+fn clear(level: &mut Level, left: u16, top: u16, right: u16, bottom: u16) {
+    for x in left..right {
+        for y in top..bottom {
+            level.remove_block(x, y);
+        }
+    }
+}
+
+// Small deterministic generator (SplitMix64), so the same seed always gives the same level
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+// Seed for levels which don't have to be reproduced
+pub fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocks(level: &Level) -> Vec<(u16, u16, BlockType)> {
+        level
+            .blocks
+            .iter_region(0, 0, level.width, level.height)
+            .map(|block| (block.x, block.y, block.block_type))
+            .collect()
+    }
+
+    fn spawns(level: &Level) -> Vec<(u16, u16)> {
+        level
+            .tanks
+            .iter()
+            .flatten()
+            .map(|tank| (tank.x, tank.y))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_level() {
+        for players in 2..=4 {
+            let first = generate(&GeneratorParameters::new(1234, players));
+            let second = generate(&GeneratorParameters::new(1234, players));
+
+            assert!(blocks(&first) == blocks(&second));
+            assert_eq!(spawns(&first), spawns(&second));
+            assert_eq!(spawns(&first).len(), players as usize);
+        }
+
+        let other = generate(&GeneratorParameters::new(4321, 2));
+        assert!(blocks(&generate(&GeneratorParameters::new(1234, 2))) != blocks(&other));
+    }
+
+    #[test]
+    fn generated_spawns_are_connected() {
+        for seed in 0..20 {
+            for players in 2..=4 {
+                let level = generate(&GeneratorParameters::new(seed, players));
+                assert!(
+                    all_spawns_connected(&level),
+                    "seed {seed}, {players} players"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn carved_paths_reach_the_center() {
+        let mut parameters = GeneratorParameters::new(7, 4);
+        parameters.densities = [0, 100, 0, 0];
+        let mut level = Level::new();
+        place_spawns(&mut level, &mut Random::new(7), Symmetry::Quadrant, 4);
+        place_blocks(
+            &mut level,
+            &mut Random::new(7),
+            Symmetry::Quadrant,
+            &parameters.densities,
+        );
+        assert!(!all_spawns_connected(&level));
+
+        carve_paths_to_center(&mut level);
+
+        let center = (DEFAULT_LEVEL_SIZE - TANK_SIZE) / 2;
+        let grid = block_grid(&level);
+        for tank in level.tanks.iter().flatten() {
+            assert!(reachable_positions(&grid, tank).contains(&(center, center)));
+        }
+        assert!(all_spawns_connected(&level));
+    }
+}
@@ -0,0 +1,227 @@
+use super::{
+    block::{Block, BlockType, BlockVariant},
+    tile::Tile,
+    MAX_LEVEL_SIZE,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const SIZE: usize = MAX_LEVEL_SIZE as usize;
+
+// Blocks of the level indexed by their position. It spans the largest map, so resizing the level
+// only clears the cells left outside. Saved as a list of blocks, like before the grid.
+#[derive(Clone)]
+pub struct Grid {
+    cells: Vec<Option<(BlockType, BlockVariant)>>,
+}
+
+impl Grid {
+    pub fn new() -> Self {
+        Grid {
+            cells: vec![None; SIZE * SIZE],
+        }
+    }
+
+    fn index(x: u16, y: u16) -> Option<usize> {
+        if x < MAX_LEVEL_SIZE && y < MAX_LEVEL_SIZE {
+            Some(y as usize * SIZE + x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, x: u16, y: u16) -> Option<Block> {
+        let (block_type, block_variant) = self.cells[Grid::index(x, y)?]?;
+
+        Some(Block {
+            x,
+            y,
+            block_type,
+            block_variant,
+        })
+    }
+
+    // Replaces the previous block in the position and returns it. Blocks beyond the largest map
+    // are dropped.
+    pub fn set(&mut self, block: Block) -> Option<Block> {
+        let i = Grid::index(block.x, block.y)?;
+        let previous = self.get(block.x, block.y);
+        self.cells[i] = Some((block.block_type, block.block_variant));
+        previous
+    }
+
+    pub fn remove(&mut self, x: u16, y: u16) -> Option<Block> {
+        let block = self.get(x, y)?;
+        self.cells[Grid::index(x, y)?] = None;
+        Some(block)
+    }
+
+    pub fn tile(&self, x: u16, y: u16) -> Tile {
+        let mut tile = Tile::empty(x, y);
+
+        tile.quadrants = BlockVariant::ALL.map(|quadrant| {
+            let (x, y) = tile.cell(quadrant);
+            self.get(x, y)
+                .map(|block| (block.block_type, block.block_variant))
+        });
+
+        tile
+    }
+
+    // Replaces all four quadrants, emptying the ones without a block, and returns the previous tile
+    pub fn set_tile(&mut self, tile: &Tile) -> Tile {
+        let previous = self.tile(tile.x, tile.y);
+
+        for quadrant in BlockVariant::ALL {
+            let (x, y) = tile.cell(quadrant);
+            self.remove(x, y);
+        }
+
+        for block in tile.blocks() {
+            self.set(block);
+        }
+
+        previous
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(None);
+    }
+
+    // Row by row, from the top left corner
+    pub fn iter(&self) -> impl Iterator<Item = Block> + '_ {
+        self.iter_region(0, 0, MAX_LEVEL_SIZE, MAX_LEVEL_SIZE)
+    }
+
+    pub fn iter_region(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+    ) -> impl Iterator<Item = Block> + '_ {
+        let right = x.saturating_add(width).min(MAX_LEVEL_SIZE);
+        let bottom = y.saturating_add(height).min(MAX_LEVEL_SIZE);
+
+        (y.min(bottom)..bottom)
+            .flat_map(move |y| (x.min(right)..right).map(move |x| (x, y)))
+            .filter_map(|(x, y)| self.get(x, y))
+    }
+
+    // Removes the blocks right of or below the given size
+    pub fn clear_outside(&mut self, width: u16, height: u16) {
+        for (i, cell) in self.cells.iter_mut().enumerate() {
+            if i % SIZE >= width as usize || i / SIZE >= height as usize {
+                *cell = None;
+            }
+        }
+    }
+}
+
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut grid = Grid::new();
+
+        for block in Vec::<Block>::deserialize(deserializer)? {
+            grid.set(block);
+        }
+
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::level::tile::TILE_SIZE;
+
+    fn block(x: u16, y: u16, block_type: BlockType) -> Block {
+        Block {
+            x,
+            y,
+            block_type,
+            block_variant: BlockVariant::ALL
+                .into_iter()
+                .find(|variant| variant.offset() == (x % TILE_SIZE, y % TILE_SIZE))
+                .unwrap(),
+        }
+    }
+
+    fn positions(blocks: impl Iterator<Item = Block>) -> Vec<(u16, u16)> {
+        blocks.map(|block| (block.x, block.y)).collect()
+    }
+
+    #[test]
+    fn set_replaces_block() {
+        let mut grid = Grid::new();
+        grid.set(block(3, 4, BlockType::Brick));
+        grid.set(block(3, 4, BlockType::Water));
+
+        assert!(matches!(
+            grid.get(3, 4).map(|block| block.block_type),
+            Some(BlockType::Water)
+        ));
+        assert_eq!(grid.iter().count(), 1);
+        assert!(grid.remove(3, 4).is_some());
+        assert!(grid.get(3, 4).is_none());
+    }
+
+    #[test]
+    fn region_clipped_to_grid() {
+        let mut grid = Grid::new();
+        for (x, y) in [(0, 0), (5, 5), (6, 5), (99, 99)] {
+            grid.set(block(x, y, BlockType::Concrete));
+        }
+
+        assert_eq!(positions(grid.iter_region(5, 5, 2, 1)), [(5, 5), (6, 5)]);
+        assert_eq!(positions(grid.iter_region(90, 90, 20, 20)), [(99, 99)]);
+        assert_eq!(positions(grid.iter()), [(0, 0), (5, 5), (6, 5), (99, 99)]);
+    }
+
+    #[test]
+    fn clear_outside_size() {
+        let mut grid = Grid::new();
+        for (x, y) in [(1, 1), (30, 1), (1, 30)] {
+            grid.set(block(x, y, BlockType::Leaves));
+        }
+        grid.clear_outside(30, 30);
+
+        assert_eq!(positions(grid.iter()), [(1, 1)]);
+    }
+
+    #[test]
+    fn tiles_read_from_cells() {
+        let mut grid = Grid::new();
+        grid.set_tile(&Tile::full(2, 3, BlockType::Brick));
+
+        assert!(grid.tile(2, 3).block_type() == Some(BlockType::Brick));
+        assert_eq!(positions(grid.iter()), [(4, 6), (5, 6), (4, 7), (5, 7)]);
+
+        grid.set(block(5, 7, BlockType::Leaves));
+        assert!(grid.tile(2, 3).block_type().is_none());
+
+        grid.set_tile(&Tile::empty(2, 3));
+        assert!(grid.tile(2, 3).blocks().next().is_none());
+        assert_eq!(grid.iter().count(), 0);
+    }
+
+    #[test]
+    fn saved_as_list() {
+        let mut grid = Grid::new();
+        grid.set(block(2, 1, BlockType::Brick));
+
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"x":2,"y":1,"block_type":"Brick","block_variant":"LeftBottom"}]"#
+        );
+
+        let loaded: Grid = serde_json::from_str(&json).unwrap();
+        assert_eq!(positions(loaded.iter()), [(2, 1)]);
+    }
+}
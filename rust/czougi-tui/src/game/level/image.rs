@@ -0,0 +1,355 @@
+use std::env;
+
+use super::{
+    braille::{block_dot, tank_dot, DOTS_PER_CELL},
+    camera::{Camera, MapView},
+    Level,
+};
+use crate::game::{colors::rgb, render_target::RenderTarget, theme::Theme};
+use crossterm::{cursor, queue, style::Color, Result};
+
+// Size of a braille dot in the pixels of a Sixel image. Kitty scales the image to the view itself.
+const SIXEL_DOT_SIZE: u16 = 4;
+
+// Longest part of the image data sent in a single Kitty escape sequence
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+// Kitty replaces the image with the same id, instead of stacking the images of the following frames
+const KITTY_IMAGE_ID: u32 = 1;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GraphicsProtocol {
+    Sixel,
+    Kitty,
+}
+
+impl GraphicsProtocol {
+    // Guessed from the environment, as asking the terminal would mean reading its answer from the
+    // input shared with the game
+    pub fn detect() -> Option<Self> {
+        let term = env::var("TERM").unwrap_or_default();
+        let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+
+        if env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term == "xterm-ghostty"
+        {
+            Some(GraphicsProtocol::Kitty)
+        } else if term.contains("sixel")
+            || ["mlterm", "foot", "foot-extra", "yaft-256color"].contains(&term.as_str())
+            || ["WezTerm", "mintty", "iTerm.app"].contains(&term_program.as_str())
+        {
+            Some(GraphicsProtocol::Sixel)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct Bitmap {
+    pub width: u16,
+    pub height: u16,
+    pub pixels: Vec<[u8; 3]>, // Row by row
+}
+
+impl Bitmap {
+    // Part of the map seen through the camera, with the braille patterns of blocks and tanks
+    pub fn from_level(level: &Level, camera: &Camera, theme: &Theme, dot_size: u16) -> Self {
+        let width = camera.width * DOTS_PER_CELL * dot_size;
+        let height = camera.height * DOTS_PER_CELL * dot_size;
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+
+        for y in 0..height {
+            for x in 0..width {
+                let color = dot_color(
+                    level,
+                    theme,
+                    camera.x * DOTS_PER_CELL + x / dot_size,
+                    camera.y * DOTS_PER_CELL + y / dot_size,
+                );
+                pixels.push(rgb(color));
+            }
+        }
+
+        Bitmap {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+// Color of the dot at the position measured in dots from the left top corner of the map
+fn dot_color(level: &Level, theme: &Theme, x: u16, y: u16) -> Color {
+    let (cell_x, cell_y) = (x / DOTS_PER_CELL, y / DOTS_PER_CELL);
+
+    if let Some((player_number, tank)) = level.tank_at(cell_x, cell_y) {
+        return if tank_dot(
+            tank.direction,
+            x - tank.x * DOTS_PER_CELL,
+            y - tank.y * DOTS_PER_CELL,
+        ) {
+            theme.player_color(player_number)
+        } else {
+            Color::Black
+        };
+    }
+
+    if let Some(block) = level.block_at(cell_x, cell_y) {
+        return if block_dot(
+            block.block_type,
+            block.block_variant,
+            x % DOTS_PER_CELL,
+            y % DOTS_PER_CELL,
+        ) {
+            theme.block(block.block_type).foreground
+        } else {
+            theme.block(block.block_type).background
+        };
+    }
+
+    level.cell_color(cell_x, cell_y, theme)
+}
+
+// Draws the whole view as a single image
+pub fn draw_map(
+    view: &mut MapView<impl RenderTarget>,
+    level: &Level,
+    protocol: GraphicsProtocol,
+) -> Result<()> {
+    let (camera, theme) = (view.camera, view.theme);
+    let bytes = match protocol {
+        GraphicsProtocol::Sixel => {
+            encode_sixel(&Bitmap::from_level(level, camera, theme, SIXEL_DOT_SIZE))
+        }
+        GraphicsProtocol::Kitty => encode_kitty(
+            &Bitmap::from_level(level, camera, theme, 1),
+            camera.view_columns(),
+            camera.view_rows(),
+        ),
+    };
+
+    let (x, y) = (view.horizontal_margin, view.vertical_margin);
+    queue!(view.target, cursor::MoveTo(x, y))?;
+    view.target.write_all(&bytes)?;
+    Ok(())
+}
+
+pub fn encode_sixel(bitmap: &Bitmap) -> Vec<u8> {
+    let mut palette: Vec<[u8; 3]> = vec![];
+    let indices: Vec<usize> = bitmap
+        .pixels
+        .iter()
+        .map(
+            |pixel| match palette.iter().position(|color| color == pixel) {
+                Some(index) => index,
+                None => {
+                    palette.push(*pixel);
+                    palette.len() - 1
+                }
+            },
+        )
+        .collect();
+
+    let (width, height) = (bitmap.width as usize, bitmap.height as usize);
+    let mut bytes = b"\x1bPq".to_vec();
+    bytes.extend(format!("\"1;1;{};{}", width, height).bytes());
+
+    // Sixel colors are given in percents
+    for (index, [r, g, b]) in palette.iter().enumerate() {
+        let percents = [r, g, b].map(|channel| *channel as u16 * 100 / 255);
+        bytes.extend(
+            format!(
+                "#{};2;{};{};{}",
+                index, percents[0], percents[1], percents[2]
+            )
+            .bytes(),
+        );
+    }
+
+    // Every band is six rows of pixels, drawn once for every color in it
+    for band_top in (0..height).step_by(6) {
+        if band_top > 0 {
+            bytes.push(b'-');
+        }
+
+        let mut first_color = true;
+
+        for color in 0..palette.len() {
+            let sixels: Vec<u8> = (0..width)
+                .map(|x| {
+                    let bits = (0..6)
+                        .filter(|row| {
+                            let y = band_top + row;
+                            y < height && indices[y * width + x] == color
+                        })
+                        .fold(0, |bits, row| bits | 1 << row);
+                    b'?' + bits
+                })
+                .collect();
+
+            if sixels.iter().all(|sixel| *sixel == b'?') {
+                continue;
+            }
+
+            // Goes back to the beginning of the band
+            if !first_color {
+                bytes.push(b'$');
+            }
+            first_color = false;
+
+            bytes.extend(format!("#{}", color).bytes());
+            push_run_length_encoded(&mut bytes, &sixels);
+        }
+    }
+
+    bytes.extend(b"\x1b\\");
+    bytes
+}
+
+fn push_run_length_encoded(bytes: &mut Vec<u8>, sixels: &[u8]) {
+    let mut i = 0;
+
+    while i < sixels.len() {
+        let run = sixels[i..]
+            .iter()
+            .take_while(|sixel| **sixel == sixels[i])
+            .count();
+
+        if run > 3 {
+            bytes.extend(format!("!{}", run).bytes());
+            bytes.push(sixels[i]);
+        } else {
+            bytes.extend(&sixels[i..i + run]);
+        }
+
+        i += run;
+    }
+}
+
+// Image scaled by the terminal to the given number of columns and rows
+pub fn encode_kitty(bitmap: &Bitmap, columns: u16, rows: u16) -> Vec<u8> {
+    let data: Vec<u8> = bitmap.pixels.iter().flatten().copied().collect();
+    let payload = base64(&data);
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let mut bytes = vec![];
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+
+        // Only the first chunk describes the image, the cursor stays where it was
+        let control = if i == 0 {
+            format!(
+                "a=T,f=24,s={},v={},c={},r={},i={},C=1,q=2,m={}",
+                bitmap.width, bitmap.height, columns, rows, KITTY_IMAGE_ID, more
+            )
+        } else {
+            format!("m={}", more)
+        };
+
+        bytes.extend(format!("\x1b_G{};", control).bytes());
+        bytes.extend(*chunk);
+        bytes.extend(b"\x1b\\");
+    }
+
+    bytes
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0_u32, |bits, (i, byte)| {
+            bits | (*byte as u32) << (16 - 8 * i)
+        });
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 3] = [255, 0, 0];
+    const BLUE: [u8; 3] = [0, 0, 255];
+
+    fn bitmap(width: u16, height: u16, pixels: Vec<[u8; 3]>) -> Bitmap {
+        Bitmap {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    #[test]
+    fn sixel_single_color() {
+        let bytes = encode_sixel(&bitmap(2, 1, vec![RED; 2]));
+
+        assert_eq!(bytes, b"\x1bPq\"1;1;2;1#0;2;100;0;0#0@@\x1b\\".to_vec());
+    }
+
+    #[test]
+    fn sixel_run_length_encoding() {
+        let bytes = encode_sixel(&bitmap(5, 6, vec![RED; 30]));
+
+        assert_eq!(bytes, b"\x1bPq\"1;1;5;6#0;2;100;0;0#0!5~\x1b\\".to_vec());
+    }
+
+    #[test]
+    fn sixel_colors_and_bands() {
+        // Red row on top of a blue one, then a third row in the next band
+        let mut pixels = vec![RED; 2];
+        pixels.extend([BLUE; 2]);
+        pixels.extend(vec![RED; 12]);
+        let bytes = encode_sixel(&bitmap(2, 8, pixels));
+
+        assert_eq!(
+            bytes,
+            b"\x1bPq\"1;1;2;8#0;2;100;0;0#1;2;0;0;100#0||$#1AA-#0BB\x1b\\".to_vec()
+        );
+    }
+
+    #[test]
+    fn kitty_single_chunk() {
+        let bytes = encode_kitty(&bitmap(1, 1, vec![RED]), 2, 1);
+
+        assert_eq!(
+            bytes,
+            b"\x1b_Ga=T,f=24,s=1,v=1,c=2,r=1,i=1,C=1,q=2,m=0;/wAA\x1b\\".to_vec()
+        );
+    }
+
+    #[test]
+    fn kitty_chunks() {
+        // 3072 bytes of data are exactly one chunk of base64
+        let bytes = encode_kitty(&bitmap(1025, 1, vec![RED; 1025]), 1, 1);
+        let text = String::from_utf8(bytes).unwrap();
+        let sequences: Vec<&str> = text.split("\x1b\\").filter(|s| !s.is_empty()).collect();
+
+        assert_eq!(sequences.len(), 2);
+        assert!(sequences[0].starts_with("\x1b_Ga=T,f=24,s=1025,v=1,c=1,r=1,i=1,C=1,q=2,m=1;"));
+        assert_eq!(
+            sequences[0].len(),
+            sequences[0].find(';').unwrap() + 1 + 4096
+        );
+        assert_eq!(sequences[1], "\x1b_Gm=0;/wAA");
+    }
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64(b"a"), "YQ==");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"abc"), "YWJj");
+    }
+}
@@ -1,92 +1,385 @@
 pub mod block;
+pub mod braille;
+pub mod camera;
+pub mod generator;
+pub mod grid;
+pub mod image;
+pub mod pathfinding;
+pub mod statistics;
 pub mod tank;
+pub mod templates;
+pub mod tile;
+pub mod validation;
 
-use self::{block::Block, tank::Tank};
+use self::{
+    block::Block,
+    camera::{MapRenderer, MapView},
+    grid::Grid,
+    tank::{Spawn, Tank, TANK_SIZE},
+    tile::Tile,
+};
+use super::{paths::data_dir, render_target::RenderTarget, theme::Theme};
 use crossterm::{
     cursor, queue,
     style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
     Result,
 };
-use std::{collections::HashSet, io::Stdout};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Level {
-    pub blocks: HashSet<Block>,
+    pub title: String,
+    pub author: String,
+    pub description: String,
+    #[serde(default = "default_level_size")]
+    pub width: u16,
+    #[serde(default = "default_level_size")]
+    pub height: u16,
+    pub blocks: Grid,
     pub tanks: [Option<Tank>; 4],
+    #[serde(default)]
+    pub spawns: Vec<Spawn>,
+}
+
+// Part of the screen showing the map, measured in cells and in columns
+pub const MAP_VIEW_SIZE: u16 = 50;
+pub const MAP_VIEW_WIDTH: u16 = 100;
+
+pub const DEFAULT_LEVEL_SIZE: u16 = 50;
+pub const MIN_LEVEL_SIZE: u16 = 26; // Size of the classic Battle City map
+pub const MAX_LEVEL_SIZE: u16 = 100;
+
+// Levels saved before maps could be resized are all of the default size
+fn default_level_size() -> u16 {
+    DEFAULT_LEVEL_SIZE
 }
 
-pub const LEVEL_SIZE: u16 = 50;
-pub const LEVEL_MAP_WIDTH: u16 = 100;
+pub const MAX_TITLE_LENGTH: usize = 64;
+pub const MAX_AUTHOR_LENGTH: usize = 64;
+pub const MAX_DESCRIPTION_LENGTH: usize = 256;
 
 impl Level {
     pub fn new() -> Self {
         Level {
-            blocks: HashSet::new(),
+            title: String::from("Untitled"),
+            author: String::new(),
+            description: String::new(),
+            width: DEFAULT_LEVEL_SIZE,
+            height: DEFAULT_LEVEL_SIZE,
+            blocks: Grid::new(),
             tanks: [None, None, None, None],
+            spawns: vec![],
         }
     }
 
-    pub fn draw(
-        &self,
-        stdout: &mut Stdout,
-        horizontal_margin: u16,
-        vertical_margin: u16,
-        x: u16,
-        y: u16,
-        width: u16,
-        height: u16,
-    ) -> Result<()> {
-        queue!(stdout, SetBackgroundColor(Color::Black))?;
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
 
-        for (tank, player_number) in self.tanks.iter().zip(0..4 as u8) {
-            if let Some(tank) = tank {
-                if tank.x >= x && tank.x < x + width && tank.y >= y && tank.y < y + height {
-                    tank.draw(stdout, horizontal_margin, vertical_margin, player_number)?;
-                }
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()
+    }
+
+    // Path in the levels directory based on the title, used when saving a level for the first time
+    pub fn default_path(&self) -> Option<PathBuf> {
+        data_dir().map(|dir| free_path(&dir.join("levels"), &file_stem(&self.title)))
+    }
+
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x < self.width && y < self.height
+    }
+
+    // Checks if a whole tank with its top left corner in the given position is inside the map
+    pub fn fits_tank(&self, x: u16, y: u16) -> bool {
+        x + TANK_SIZE <= self.width && y + TANK_SIZE <= self.height
+    }
+
+    // Changes the size of the map, keeping its top left corner in place and removing whatever
+    // does not fit anymore
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.width = width.clamp(MIN_LEVEL_SIZE, MAX_LEVEL_SIZE);
+        self.height = height.clamp(MIN_LEVEL_SIZE, MAX_LEVEL_SIZE);
+
+        let (width, height) = (self.width, self.height);
+        self.blocks.clear_outside(width, height);
+
+        for tank in self.tanks.iter_mut() {
+            if tank
+                .as_ref()
+                .is_some_and(|tank| tank.x + TANK_SIZE > width || tank.y + TANK_SIZE > height)
+            {
+                *tank = None;
             }
         }
 
-        let filtered_blocks = self.blocks.iter().filter(|block| {
-            block.x >= x && block.x < x + width && block.y >= y && block.y < y + height
-        });
+        self.spawns
+            .retain(|spawn| spawn.x + TANK_SIZE <= width && spawn.y + TANK_SIZE <= height);
+    }
+
+    // Puts the block in its position, replacing the previous one
+    pub fn set_block(&mut self, block: Block) -> Option<Block> {
+        self.blocks.set(block)
+    }
+
+    pub fn remove_block(&mut self, x: u16, y: u16) -> Option<Block> {
+        self.blocks.remove(x, y)
+    }
+
+    pub fn block_at(&self, x: u16, y: u16) -> Option<Block> {
+        self.blocks.get(x, y)
+    }
+
+    // Takes the position in tiles, like the rest of the tile API
+    pub fn tile(&self, tile_x: u16, tile_y: u16) -> Tile {
+        self.blocks.tile(tile_x, tile_y)
+    }
+
+    pub fn set_tile(&mut self, tile: &Tile) -> Tile {
+        self.blocks.set_tile(tile)
+    }
+
+    // Returns the player number and the tank covering the given position
+    pub fn tank_at(&self, x: u16, y: u16) -> Option<(u8, &Tank)> {
+        self.tanks
+            .iter()
+            .zip(0..4_u8)
+            .find_map(|(tank, player_number)| match tank {
+                Some(tank)
+                    if x >= tank.x
+                        && x < tank.x + TANK_SIZE
+                        && y >= tank.y
+                        && y < tank.y + TANK_SIZE =>
+                {
+                    Some((player_number, tank))
+                }
+                _ => None,
+            })
+    }
+
+    // Returns the index of the extra spawn covering the given position
+    pub fn spawn_at(&self, x: u16, y: u16) -> Option<usize> {
+        self.spawns.iter().position(|spawn| {
+            x >= spawn.x && x < spawn.x + TANK_SIZE && y >= spawn.y && y < spawn.y + TANK_SIZE
+        })
+    }
+
+    // Draws the part of the map seen through the camera
+    pub fn draw(&self, view: &mut MapView<impl RenderTarget>) -> Result<()> {
+        let camera = view.camera;
+        let (x, y, width, height) = (camera.x, camera.y, camera.width, camera.height);
 
-        for block in filtered_blocks.clone() {
-            block.draw(stdout, horizontal_margin, vertical_margin)?;
+        if let MapRenderer::Image(protocol) = camera.renderer {
+            return image::draw_map(view, self, protocol);
         }
 
+        if camera.renderer == MapRenderer::Compact {
+            return self.draw_compact(view);
+        }
+
+        queue!(view.target, SetBackgroundColor(Color::Black))?;
+
         let mut background_tiles = vec![];
-        background_tiles.reserve((width * height) as usize);
+        // Part of the view beyond a map smaller than the view
+        let mut outside_tiles = vec![];
 
-        for x in x..x + width {
-            for y in y..y + height {
-                background_tiles.push((x, y));
+        for y in y..y + height {
+            for x in x..x + width {
+                match self.blocks.get(x, y) {
+                    Some(block) => block.draw(view)?,
+                    None if self.contains(x, y) => background_tiles.push((x, y)),
+                    None => outside_tiles.push((x, y)),
+                }
             }
         }
 
-        for block in filtered_blocks {
-            background_tiles.retain(|(x, y)| *x != block.x || *y != block.y);
+        // Blocks leave their own background color behind
+        queue!(
+            view.target,
+            SetBackgroundColor(Color::Black),
+            SetForegroundColor(Color::DarkGrey)
+        )?;
+
+        for (x, y) in outside_tiles {
+            let screen_x = view.horizontal_margin + 2 * (x - camera.x);
+            let screen_y = view.vertical_margin + y - camera.y;
+
+            queue!(
+                view.target,
+                cursor::MoveTo(screen_x, screen_y),
+                Print(camera.shade())
+            )?;
         }
 
-        queue!(stdout, SetForegroundColor(Color::White),)?;
+        queue!(view.target, SetForegroundColor(Color::White),)?;
 
         for (x, y) in background_tiles {
-            let graphics = if x % 2 == 1 { " │" } else { "  " };
+            let graphics = match (x % 2 == 1, camera.renderer) {
+                (true, MapRenderer::Ascii) => " |",
+                (true, _) => " │",
+                (false, _) => "  ",
+            };
 
             let horizontal_line = y % 2 == 1;
 
             if horizontal_line {
-                queue!(stdout, SetAttribute(Attribute::Underlined))?;
+                queue!(view.target, SetAttribute(Attribute::Underlined))?;
             }
 
+            let screen_x = view.horizontal_margin + 2 * (x - camera.x);
+            let screen_y = view.vertical_margin + y - camera.y;
+
             queue!(
-                stdout,
-                cursor::MoveTo(x * 2 + horizontal_margin, y + vertical_margin),
+                view.target,
+                cursor::MoveTo(screen_x, screen_y),
                 Print(graphics)
             )?;
 
             if horizontal_line {
-                queue!(stdout, SetAttribute(Attribute::Reset))?;
+                queue!(view.target, SetAttribute(Attribute::NoUnderline))?;
+            }
+        }
+
+        // Spawns and tanks go on top of the background, including ones only partly in the view
+        let overlaps = |object_x: u16, object_y: u16| {
+            object_x + TANK_SIZE > x
+                && object_x < x + width
+                && object_y + TANK_SIZE > y
+                && object_y < y + height
+        };
+
+        queue!(view.target, SetBackgroundColor(Color::Black))?;
+
+        for spawn in self.spawns.iter() {
+            if overlaps(spawn.x, spawn.y) {
+                spawn.draw(view)?;
+            }
+        }
+
+        for (tank, player_number) in self.tanks.iter().zip(0..4_u8) {
+            if let Some(tank) = tank {
+                if overlaps(tank.x, tank.y) {
+                    tank.draw(view, player_number)?;
+                }
             }
         }
 
         Ok(())
     }
+
+    // Packs two cells into every character with a half block, the upper cell in its foreground
+    // color and the lower one in its background color
+    fn draw_compact(&self, view: &mut MapView<impl RenderTarget>) -> Result<()> {
+        let (camera, theme) = (view.camera, view.theme);
+        // Rows are paired starting from the top of the view
+        let last_row = camera.y + camera.height;
+
+        for top in (camera.y..last_row).step_by(2) {
+            for x in camera.x..camera.x + camera.width {
+                let (screen_x, screen_y) = match view.screen_position(x, top) {
+                    Some(position) => position,
+                    None => continue,
+                };
+                let bottom_color = if top + 1 < last_row {
+                    self.cell_color(x, top + 1, theme)
+                } else {
+                    Color::Black
+                };
+
+                queue!(
+                    view.target,
+                    cursor::MoveTo(screen_x, screen_y),
+                    SetForegroundColor(self.cell_color(x, top, theme)),
+                    SetBackgroundColor(bottom_color),
+                    Print("▀"),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Single color standing for whatever is in the cell
+    pub fn cell_color(&self, x: u16, y: u16, theme: &Theme) -> Color {
+        if !self.contains(x, y) {
+            return Color::DarkGrey;
+        }
+
+        if let Some((player_number, _)) = self.tank_at(x, y) {
+            return theme.player_color(player_number);
+        }
+
+        // Spawns are drawn as outlines
+        if let Some(spawn) = self.spawn_at(x, y).map(|i| &self.spawns[i]) {
+            let (dx, dy) = (x - spawn.x, y - spawn.y);
+
+            if dx == 0 || dy == 0 || dx == TANK_SIZE - 1 || dy == TANK_SIZE - 1 {
+                return theme.spawn_color(spawn.player_number);
+            }
+        }
+
+        self.block_at(x, y).map_or(Color::Black, |block| {
+            theme.block(block.block_type).foreground
+        })
+    }
+}
+
+// Lowercase words of the title joined with underscores, so any title gives a valid file name
+fn file_stem(title: &str) -> String {
+    let words: Vec<String> = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    if words.is_empty() {
+        String::from("level")
+    } else {
+        words.join("_")
+    }
+}
+
+// First of `stem.json`, `stem_2.json`, ... that does not exist yet, so other levels are not overwritten
+fn free_path(dir: &Path, stem: &str) -> PathBuf {
+    (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("{}.json", stem)),
+            n => dir.join(format!("{}_{}.json", stem, n)),
+        })
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn file_stem_of_titles() {
+        assert_eq!(file_stem("My Level"), "my_level");
+        assert_eq!(file_stem("  Tanks: vs. bricks!! "), "tanks_vs_bricks");
+        assert_eq!(file_stem("../../etc/passwd"), "etc_passwd");
+        assert_eq!(file_stem("Żółw"), "żółw");
+        assert_eq!(file_stem("?!"), "level");
+    }
+
+    #[test]
+    fn free_path_skips_existing_files() {
+        let dir = env::temp_dir().join(format!("czougi-free-path-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(free_path(&dir, "arena"), dir.join("arena.json"));
+
+        File::create(dir.join("arena.json")).unwrap();
+        File::create(dir.join("arena_2.json")).unwrap();
+        assert_eq!(free_path(&dir, "arena"), dir.join("arena_3.json"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
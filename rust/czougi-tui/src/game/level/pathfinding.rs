@@ -0,0 +1,129 @@
+use super::{
+    block::BlockType,
+    tank::{Tank, TANK_SIZE},
+    Level,
+};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+// Extra cost of driving into a brick cell, as the tank has to shoot it down first
+pub const BRICK_COST: u32 = 4;
+
+// Block types of all cells in the map, indexed by [y][x]
+pub type BlockGrid = Vec<Vec<Option<BlockType>>>;
+
+// Concrete and water stop tanks for good, bricks can be shot through and leaves driven through
+pub fn is_impassable(block_type: BlockType) -> bool {
+    matches!(block_type, BlockType::Concrete | BlockType::Water)
+}
+
+pub fn block_grid(level: &Level) -> BlockGrid {
+    let mut grid = vec![vec![None; level.width as usize]; level.height as usize];
+
+    for block in level.blocks.iter_region(0, 0, level.width, level.height) {
+        grid[block.y as usize][block.x as usize] = Some(block.block_type);
+    }
+
+    grid
+}
+
+pub fn can_tank_stand(grid: &BlockGrid, x: u16, y: u16) -> bool {
+    let (width, height) = (grid.first().map_or(0, Vec::len) as u16, grid.len() as u16);
+
+    if x + TANK_SIZE > width || y + TANK_SIZE > height {
+        return false;
+    }
+
+    (y..y + TANK_SIZE).all(|y| {
+        (x..x + TANK_SIZE).all(|x| !matches!(grid[y as usize][x as usize], Some(block_type) if is_impassable(block_type)))
+    })
+}
+
+// Cheapest cost of driving from where the tank stands to every reachable position of its top left corner
+pub fn path_costs(grid: &BlockGrid, tank: &Tank) -> HashMap<(u16, u16), u32> {
+    let mut costs = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    if can_tank_stand(grid, tank.x, tank.y) {
+        costs.insert((tank.x, tank.y), 0);
+        queue.push(Reverse((0, tank.x, tank.y)));
+    }
+
+    while let Some(Reverse((cost, x, y))) = queue.pop() {
+        if costs.get(&(x, y)).is_some_and(|best| cost > *best) {
+            continue;
+        }
+
+        for (next_x, next_y) in neighbours(x, y) {
+            if !can_tank_stand(grid, next_x, next_y) {
+                continue;
+            }
+
+            let next_cost = cost + 1 + BRICK_COST * entered_bricks(grid, (x, y), (next_x, next_y));
+
+            if costs
+                .get(&(next_x, next_y))
+                .is_none_or(|best| next_cost < *best)
+            {
+                costs.insert((next_x, next_y), next_cost);
+                queue.push(Reverse((next_cost, next_x, next_y)));
+            }
+        }
+    }
+
+    costs
+}
+
+// Positions of the top left corner which the tank can drive to from where it stands
+pub fn reachable_positions(grid: &BlockGrid, tank: &Tank) -> HashSet<(u16, u16)> {
+    path_costs(grid, tank).into_keys().collect()
+}
+
+// Cells covered by a tank standing in any of the given positions
+pub fn covered_cells<'a>(positions: impl Iterator<Item = &'a (u16, u16)>) -> HashSet<(u16, u16)> {
+    let mut cells = HashSet::new();
+
+    for (x, y) in positions {
+        for cell_y in *y..y + TANK_SIZE {
+            for cell_x in *x..x + TANK_SIZE {
+                cells.insert((cell_x, cell_y));
+            }
+        }
+    }
+
+    cells
+}
+
+pub fn neighbours(x: u16, y: u16) -> impl Iterator<Item = (u16, u16)> {
+    [
+        (x.checked_sub(1), Some(y)),
+        (x.checked_add(1), Some(y)),
+        (Some(x), y.checked_sub(1)),
+        (Some(x), y.checked_add(1)),
+    ]
+    .into_iter()
+    .filter_map(|position| match position {
+        (Some(x), Some(y)) => Some((x, y)),
+        _ => None,
+    })
+}
+
+// Number of brick cells covered by the tank after the move that were not covered before
+fn entered_bricks(grid: &BlockGrid, from: (u16, u16), to: (u16, u16)) -> u32 {
+    let mut bricks = 0;
+
+    for y in to.1..to.1 + TANK_SIZE {
+        for x in to.0..to.0 + TANK_SIZE {
+            let covered_before =
+                x >= from.0 && x < from.0 + TANK_SIZE && y >= from.1 && y < from.1 + TANK_SIZE;
+
+            if !covered_before && matches!(grid[y as usize][x as usize], Some(BlockType::Brick)) {
+                bricks += 1;
+            }
+        }
+    }
+
+    bricks
+}
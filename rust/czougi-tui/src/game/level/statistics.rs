@@ -0,0 +1,196 @@
+use super::{block::BlockType, Level};
+
+const SYMMETRIES: [Symmetry; 3] = [
+    Symmetry::Horizontal,
+    Symmetry::Vertical,
+    Symmetry::Rotational,
+];
+
+#[derive(Copy, Clone)]
+enum Symmetry {
+    Horizontal, // Left half mirrors the right half
+    Vertical,   // Top half mirrors the bottom half
+    Rotational, // Map looks the same after turning it by 180 degrees
+}
+
+impl Symmetry {
+    fn counterpart(self, level: &Level, x: u16, y: u16) -> (u16, u16) {
+        let (last_x, last_y) = (level.width - 1, level.height - 1);
+
+        match self {
+            Symmetry::Horizontal => (last_x - x, y),
+            Symmetry::Vertical => (x, last_y - y),
+            Symmetry::Rotational => (last_x - x, last_y - y),
+        }
+    }
+}
+
+// Block counts kept up to date cell by cell, so that they do not have to be recounted after every edit
+pub struct LevelStatistics {
+    block_counts: [usize; 4],
+    symmetric_blocks: [usize; 3], // Blocks with the same block type on the other side, per symmetry
+}
+
+impl LevelStatistics {
+    pub fn new(level: &Level) -> Self {
+        let mut statistics = LevelStatistics {
+            block_counts: [0; 4],
+            symmetric_blocks: [0; 3],
+        };
+
+        for block in level.blocks.iter_region(0, 0, level.width, level.height) {
+            statistics.block_counts[block_type_index(block.block_type)] += 1;
+
+            for (i, symmetry) in SYMMETRIES.iter().enumerate() {
+                let (x, y) = symmetry.counterpart(level, block.x, block.y);
+                if block_type_at(level, x, y) == Some(block.block_type) {
+                    statistics.symmetric_blocks[i] += 1;
+                }
+            }
+        }
+
+        statistics
+    }
+
+    // Has to be called before changing the cell, together with add_cell after the change
+    pub fn remove_cell(&mut self, level: &Level, x: u16, y: u16) {
+        self.remove_cells(level, &[(x, y)]);
+    }
+
+    pub fn add_cell(&mut self, level: &Level, x: u16, y: u16) {
+        self.add_cells(level, &[(x, y)]);
+    }
+
+    // Same as remove_cell for cells changed all at once, e.g. a whole tile
+    pub fn remove_cells(&mut self, level: &Level, cells: &[(u16, u16)]) {
+        for &(x, y) in cells {
+            if let Some(block_type) = block_type_at(level, x, y) {
+                self.block_counts[block_type_index(block_type)] -= 1;
+            }
+        }
+
+        for (i, symmetry) in SYMMETRIES.iter().enumerate() {
+            self.symmetric_blocks[i] -= symmetric_blocks_of_cells(level, *symmetry, cells);
+        }
+    }
+
+    pub fn add_cells(&mut self, level: &Level, cells: &[(u16, u16)]) {
+        for &(x, y) in cells {
+            if let Some(block_type) = block_type_at(level, x, y) {
+                self.block_counts[block_type_index(block_type)] += 1;
+            }
+        }
+
+        for (i, symmetry) in SYMMETRIES.iter().enumerate() {
+            self.symmetric_blocks[i] += symmetric_blocks_of_cells(level, *symmetry, cells);
+        }
+    }
+
+    pub fn block_count(&self, block_type: BlockType) -> usize {
+        self.block_counts[block_type_index(block_type)]
+    }
+
+    pub fn total_blocks(&self) -> usize {
+        self.block_counts.iter().sum()
+    }
+
+    // Percentage of blocks matched on the other side of the map, for the most fitting symmetry
+    pub fn symmetry_score(&self) -> usize {
+        let total_blocks = self.total_blocks();
+
+        if total_blocks == 0 {
+            return 100;
+        }
+
+        self.symmetric_blocks.iter().max().unwrap_or(&0) * 100 / total_blocks
+    }
+}
+
+pub fn map_cells(level: &Level) -> usize {
+    level.width as usize * level.height as usize
+}
+
+// Cells covered neither by blocks nor by tanks. Counted cell by cell, as tanks in a level loaded
+// from a file can stand on blocks or past the edge of the map.
+pub fn free_cells(level: &Level) -> usize {
+    (0..level.width)
+        .flat_map(|x| (0..level.height).map(move |y| (x, y)))
+        .filter(|&(x, y)| level.block_at(x, y).is_none() && level.tank_at(x, y).is_none())
+        .count()
+}
+
+fn block_type_index(block_type: BlockType) -> usize {
+    match block_type {
+        BlockType::Brick => 0,
+        BlockType::Concrete => 1,
+        BlockType::Water => 2,
+        BlockType::Leaves => 3,
+    }
+}
+
+fn block_type_at(level: &Level, x: u16, y: u16) -> Option<BlockType> {
+    level.block_at(x, y).map(|block| block.block_type)
+}
+
+// Number of blocks in the cells and their counterparts which have a matching block on the other
+// side. A pair with both cells among the given ones is counted once.
+fn symmetric_blocks_of_cells(level: &Level, symmetry: Symmetry, cells: &[(u16, u16)]) -> usize {
+    cells
+        .iter()
+        .map(|&(x, y)| {
+            let block_type = match block_type_at(level, x, y) {
+                Some(block_type) => block_type,
+                None => return 0,
+            };
+            let counterpart = symmetry.counterpart(level, x, y);
+
+            if block_type_at(level, counterpart.0, counterpart.1) != Some(block_type) {
+                0
+            } else if cells.contains(&counterpart) {
+                1
+            } else {
+                2
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::level::{
+        block::{Block, BlockVariant},
+        tank::{Direction, Tank},
+    };
+
+    #[test]
+    fn free_cells_with_tank_on_blocks() {
+        let mut level = Level::new();
+        // As loaded from a file, without the checks of resizing
+        level.width = 10;
+        level.height = 10;
+
+        for x in 0..4 {
+            level.set_block(Block {
+                x,
+                y: 0,
+                block_type: BlockType::Brick,
+                block_variant: BlockVariant::LeftTop,
+            });
+        }
+
+        // Tank on top of the blocks and partly past the edge of the map
+        level.tanks[0] = Some(Tank {
+            x: 0,
+            y: 0,
+            direction: Direction::Up,
+        });
+        level.tanks[1] = Some(Tank {
+            x: 8,
+            y: 8,
+            direction: Direction::Up,
+        });
+
+        assert_eq!(free_cells(&level), 100 - 16 - 4);
+    }
+}
@@ -1,12 +1,17 @@
-use std::io::Stdout;
+use serde::{Deserialize, Serialize};
 
-use crossterm::{
-    cursor, queue,
-    style::{Color, Print, SetForegroundColor},
-    Result,
+use super::{
+    braille,
+    camera::{MapRenderer, MapView},
 };
+use crate::game::{
+    drawing_utils::{draw_clipped_lines, draw_multi_line_text},
+    render_target::RenderTarget,
+    theme::Theme,
+};
+use crossterm::{queue, style::SetForegroundColor, Result};
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
@@ -14,95 +19,144 @@ pub enum Direction {
     Right,
 }
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Tank {
     pub x: u16,
     pub y: u16,
     pub direction: Direction,
 }
 
+// Additional place where a tank can (re)appear, next to the starting positions in `Level::tanks`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Spawn {
+    pub x: u16,
+    pub y: u16,
+    pub direction: Direction,
+    pub player_number: Option<u8>, // None for spawns shared by all players
+}
+
 pub const TANK_SIZE: u16 = 4;
 
 impl Tank {
-    pub fn draw(
-        &self,
-        stdout: &mut Stdout,
-        horizontal_margin: u16,
-        vertical_margin: u16,
-        player_number: u8,
-    ) -> Result<()> {
-        draw_tank(
-            stdout,
-            self.x * 2 + horizontal_margin,
-            self.y + vertical_margin,
-            player_number,
-            self.direction,
-        )
+    // Draws the part of the tank visible through the camera
+    pub fn draw(&self, view: &mut MapView<impl RenderTarget>, player_number: u8) -> Result<()> {
+        let color = view.theme.player_color(player_number);
+        queue!(view.target, SetForegroundColor(color))?;
+
+        if view.camera.renderer == MapRenderer::Braille {
+            draw_map_object(
+                view,
+                self.x,
+                self.y,
+                &braille::tank_graphics(self.direction),
+            )
+        } else if view.camera.renderer == MapRenderer::Ascii {
+            draw_map_object(
+                view,
+                self.x,
+                self.y,
+                &ascii_tank_graphics(view.theme, self.direction, player_number),
+            )
+        } else {
+            let graphics = view.theme.tank.lines(self.direction).clone();
+            draw_map_object(view, self.x, self.y, &graphics)
+        }
+    }
+}
+
+impl Spawn {
+    pub fn draw(&self, view: &mut MapView<impl RenderTarget>) -> Result<()> {
+        let color = view.theme.spawn_color(self.player_number);
+        queue!(view.target, SetForegroundColor(color))?;
+
+        if view.camera.renderer == MapRenderer::Ascii {
+            draw_map_object(view, self.x, self.y, &ascii_spawn_graphics(self.direction))
+        } else {
+            draw_map_object(view, self.x, self.y, &spawn_graphics(self.direction))
+        }
     }
 }
 
+fn draw_map_object(
+    view: &mut MapView<impl RenderTarget>,
+    x: u16,
+    y: u16,
+    graphics: &[impl AsRef<str>],
+) -> Result<()> {
+    let (columns, rows) = match view.camera.visible_part(x, y, TANK_SIZE) {
+        Some(visible_part) => visible_part,
+        None => return Ok(()),
+    };
+
+    if let Some((screen_x, screen_y)) = view.screen_position(x + columns.start, y + rows.start) {
+        draw_clipped_lines(
+            view.target,
+            graphics,
+            screen_x,
+            screen_y,
+            columns.start * 2..columns.end * 2,
+            rows,
+        )?;
+    }
+
+    Ok(())
+}
+
 pub fn draw_tank(
-    stdout: &mut Stdout,
+    target: &mut impl RenderTarget,
+    theme: &Theme,
     x: u16,
     y: u16,
     player_number: u8,
     direction: Direction,
 ) -> Result<()> {
-    let color = match player_number {
-        0 => Color::Yellow,
-        1 => Color::Blue,
-        2 => Color::Green,
-        3 => Color::Red,
-        _ => unreachable!(),
+    queue!(
+        target,
+        SetForegroundColor(theme.player_color(player_number))
+    )?;
+    draw_multi_line_text(target, theme.tank.lines(direction).iter(), x, y)
+}
+
+fn ascii_tank_graphics(theme: &Theme, direction: Direction, player_number: u8) -> Vec<String> {
+    let letter = (b'A' + player_number) as char;
+
+    theme
+        .ascii_tank
+        .lines(direction)
+        .iter()
+        .map(|line| line.replace('A', &letter.to_string()))
+        .collect()
+}
+
+// Outline of a tank with an arrow in the middle, showing the direction of the spawned tank
+fn spawn_graphics(direction: Direction) -> [String; 4] {
+    let arrow = match direction {
+        Direction::Up => "▲▲",
+        Direction::Down => "▼▼",
+        Direction::Left => "◄◄",
+        Direction::Right => "►►",
     };
 
-    queue!(stdout, SetForegroundColor(color))?;
-
-    match direction {
-        Direction::Up => queue!(
-            stdout,
-            cursor::MoveTo(x, y),
-            Print("   ▐▌   "),
-            cursor::MoveTo(x, y + 1),
-            Print("▄▄████▄▄"),
-            cursor::MoveTo(x, y + 2),
-            Print("████████"),
-            cursor::MoveTo(x, y + 3),
-            Print("██▀▀▀▀██"),
-        )?,
-        Direction::Down => queue!(
-            stdout,
-            cursor::MoveTo(x, y),
-            Print("██▄▄▄▄██"),
-            cursor::MoveTo(x, y + 1),
-            Print("████████"),
-            cursor::MoveTo(x, y + 2),
-            Print("▀▀████▀▀"),
-            cursor::MoveTo(x, y + 3),
-            Print("   ▐▌   "),
-        )?,
-        Direction::Left => queue!(
-            stdout,
-            cursor::MoveTo(x, y),
-            Print("   █████"),
-            cursor::MoveTo(x, y + 1),
-            Print("▄▄█████ "),
-            cursor::MoveTo(x, y + 2),
-            Print("▀▀█████ "),
-            cursor::MoveTo(x, y + 3),
-            Print("   █████"),
-        )?,
-        Direction::Right => queue!(
-            stdout,
-            cursor::MoveTo(x, y),
-            Print("█████   "),
-            cursor::MoveTo(x, y + 1),
-            Print(" █████▄▄"),
-            cursor::MoveTo(x, y + 2),
-            Print(" █████▀▀"),
-            cursor::MoveTo(x, y + 3),
-            Print("█████   "),
-        )?,
-    }
+    [
+        String::from("┌─    ─┐"),
+        format!("   {}   ", arrow),
+        format!("   {}   ", arrow),
+        String::from("└─    ─┘"),
+    ]
+}
 
-    Ok(())
+fn ascii_spawn_graphics(direction: Direction) -> [String; 4] {
+    let arrow = match direction {
+        Direction::Up => "^^",
+        Direction::Down => "vv",
+        Direction::Left => "<<",
+        Direction::Right => ">>",
+    };
+
+    [
+        String::from("+-    -+"),
+        format!("   {}   ", arrow),
+        format!("   {}   ", arrow),
+        String::from("+-    -+"),
+    ]
 }
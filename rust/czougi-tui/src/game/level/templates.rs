@@ -0,0 +1,102 @@
+use super::{
+    block::BlockType,
+    tank::{Direction, Tank},
+    tile::{Tile, TILE_SIZE},
+    Level, DEFAULT_LEVEL_SIZE,
+};
+
+pub const TEMPLATES: [Template; 4] = [
+    Template::Empty,
+    Template::ConcreteBorder,
+    Template::TwoPlayersMirrored,
+    Template::FourPlayersCorners,
+];
+
+// Starting points for new levels
+#[derive(Copy, Clone)]
+pub enum Template {
+    Empty,
+    ConcreteBorder,
+    TwoPlayersMirrored,
+    FourPlayersCorners,
+}
+
+impl Template {
+    pub fn name(self) -> &'static str {
+        match self {
+            Template::Empty => "Empty",
+            Template::ConcreteBorder => "Border",
+            Template::TwoPlayersMirrored => "2 players",
+            Template::FourPlayersCorners => "4 players",
+        }
+    }
+
+    pub fn build(self) -> Level {
+        let mut level = Level::new();
+        let last = DEFAULT_LEVEL_SIZE - 1;
+
+        if matches!(self, Template::Empty) {
+            return level;
+        }
+
+        fill(&mut level, 0, 0, last, 1, BlockType::Concrete);
+        fill(&mut level, 0, last - 1, last, last, BlockType::Concrete);
+        fill(&mut level, 0, 2, 1, last - 2, BlockType::Concrete);
+        fill(&mut level, last - 1, 2, last, last - 2, BlockType::Concrete);
+
+        match self {
+            Template::TwoPlayersMirrored => {
+                // Everything is symmetric to the center of the map
+                fill(&mut level, 14, 14, 35, 15, BlockType::Brick);
+                fill(&mut level, 14, 34, 35, 35, BlockType::Brick);
+                fill(&mut level, 6, 22, 11, 27, BlockType::Water);
+                fill(&mut level, 38, 22, 43, 27, BlockType::Water);
+                fill(&mut level, 22, 22, 27, 27, BlockType::Concrete);
+                fill(&mut level, 14, 22, 19, 27, BlockType::Leaves);
+                fill(&mut level, 30, 22, 35, 27, BlockType::Leaves);
+
+                level.tanks[0] = Some(Tank {
+                    x: 23,
+                    y: 44,
+                    direction: Direction::Up,
+                });
+                level.tanks[1] = Some(Tank {
+                    x: 23,
+                    y: 2,
+                    direction: Direction::Down,
+                });
+            }
+            Template::FourPlayersCorners => {
+                fill(&mut level, 24, 8, 25, 19, BlockType::Brick);
+                fill(&mut level, 24, 30, 25, 41, BlockType::Brick);
+                fill(&mut level, 8, 24, 19, 25, BlockType::Brick);
+                fill(&mut level, 30, 24, 41, 25, BlockType::Brick);
+                fill(&mut level, 22, 22, 27, 27, BlockType::Leaves);
+
+                for (player_number, (x, y, direction)) in [
+                    (2, 2, Direction::Down),
+                    (44, 2, Direction::Down),
+                    (2, 44, Direction::Up),
+                    (44, 44, Direction::Up),
+                ]
+                .into_iter()
+                .enumerate()
+                {
+                    level.tanks[player_number] = Some(Tank { x, y, direction });
+                }
+            }
+            _ => {}
+        }
+
+        level
+    }
+}
+
+// Fills the rectangle with whole tiles. Its corners are given in cells, at the edges of tiles.
+fn fill(level: &mut Level, left: u16, top: u16, right: u16, bottom: u16, block_type: BlockType) {
+    for x in left / TILE_SIZE..=right / TILE_SIZE {
+        for y in top / TILE_SIZE..=bottom / TILE_SIZE {
+            level.set_tile(&Tile::full(x, y, block_type));
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use super::block::{Block, BlockType, BlockVariant};
+
+pub const TILE_SIZE: u16 = 2;
+
+// Square of 2x2 cells aligned to the tile grid, so tile (x, y) starts at cell (2x, 2y). Each
+// quadrant is a block of its own, in the order of `BlockVariant`, so a tile can mix block types or
+// leave some quadrants empty. The variant of a quadrant's block usually matches its position, but
+// single blocks can be placed with any variant.
+#[derive(Copy, Clone, PartialEq)]
+pub struct Tile {
+    pub x: u16,
+    pub y: u16,
+    pub quadrants: [Option<(BlockType, BlockVariant)>; 4],
+}
+
+impl Tile {
+    pub fn empty(x: u16, y: u16) -> Self {
+        Tile {
+            x,
+            y,
+            quadrants: [None; 4],
+        }
+    }
+
+    // Whole tile of one block type, as placed by the full block tool
+    pub fn full(x: u16, y: u16, block_type: BlockType) -> Self {
+        Tile {
+            x,
+            y,
+            quadrants: BlockVariant::ALL.map(|block_variant| Some((block_type, block_variant))),
+        }
+    }
+
+    // Tile which the cell belongs to
+    pub fn containing(x: u16, y: u16) -> (u16, u16) {
+        (x / TILE_SIZE, y / TILE_SIZE)
+    }
+
+    pub fn cell(&self, quadrant: BlockVariant) -> (u16, u16) {
+        let (dx, dy) = quadrant.offset();
+        (self.x * TILE_SIZE + dx, self.y * TILE_SIZE + dy)
+    }
+
+    // Block type of a whole tile, which looks like one placed by the full block tool
+    pub fn block_type(&self) -> Option<BlockType> {
+        let (block_type, _) = self.quadrants[0]?;
+
+        BlockVariant::ALL
+            .into_iter()
+            .zip(self.quadrants)
+            .all(|(quadrant, block)| {
+                matches!(block, Some((other_type, block_variant))
+                    if other_type == block_type && block_variant == quadrant)
+            })
+            .then_some(block_type)
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = Block> + '_ {
+        BlockVariant::ALL
+            .into_iter()
+            .zip(self.quadrants)
+            .filter_map(|(quadrant, block)| {
+                let (block_type, block_variant) = block?;
+                let (x, y) = self.cell(quadrant);
+
+                Some(Block {
+                    x,
+                    y,
+                    block_type,
+                    block_variant,
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_tile_covers_its_cells() {
+        let tile = Tile::full(3, 1, BlockType::Water);
+        let cells: Vec<(u16, u16)> = tile.blocks().map(|block| (block.x, block.y)).collect();
+
+        assert_eq!(cells, [(6, 2), (7, 2), (6, 3), (7, 3)]);
+        assert!(tile.block_type() == Some(BlockType::Water));
+        assert_eq!(Tile::containing(7, 3), (3, 1));
+    }
+
+    #[test]
+    fn mixed_tiles() {
+        let mut tile = Tile::full(0, 0, BlockType::Brick);
+        tile.quadrants[3] = None;
+        assert!(tile.block_type().is_none());
+
+        tile.quadrants[3] = Some((BlockType::Concrete, BlockVariant::RightBottom));
+        assert!(tile.block_type().is_none());
+
+        // Right variants in the wrong places
+        tile.quadrants =
+            [BlockVariant::RightTop; 4].map(|variant| Some((BlockType::Brick, variant)));
+        assert!(tile.block_type().is_none());
+
+        assert!(Tile::empty(0, 0).block_type().is_none());
+    }
+}
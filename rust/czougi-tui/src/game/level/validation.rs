@@ -0,0 +1,232 @@
+use super::{
+    pathfinding::{block_grid, reachable_positions},
+    tank::{Tank, TANK_SIZE},
+    Level,
+};
+
+// Minimal distance between top left corners of two tanks, so that nobody spawns under fire
+pub const MIN_SPAWN_DISTANCE: u16 = 12;
+
+pub struct Warning {
+    pub message: String,
+    // Cells to highlight on the map, past its edge for things out of bounds. Empty only when the
+    // warning is about something missing altogether, e.g. no tanks at all.
+    pub cells: Vec<(u16, u16)>,
+}
+
+pub fn validate(level: &Level) -> Vec<Warning> {
+    let mut warnings = vec![];
+    let grid = block_grid(level);
+
+    let tanks: Vec<(u8, &Tank)> = level
+        .tanks
+        .iter()
+        .zip(0..4_u8)
+        .filter_map(|(tank, player_number)| tank.as_ref().map(|tank| (player_number, tank)))
+        .collect();
+
+    if tanks.len() < 2 {
+        warnings.push(Warning {
+            message: format!("Only {} of 2-4 tanks placed", tanks.len()),
+            cells: tanks
+                .iter()
+                .flat_map(|(_, tank)| tank_cells(tank))
+                .collect(),
+        });
+    } else {
+        for (player_number, tank) in tanks.iter() {
+            let reachable = reachable_positions(&grid, tank);
+            let can_reach_enemy = tanks.iter().any(|(other, enemy)| {
+                other != player_number && reachable.contains(&(enemy.x, enemy.y))
+            });
+
+            if !can_reach_enemy {
+                warnings.push(Warning {
+                    message: format!("Player {} is walled in", player_number + 1),
+                    cells: tank_cells(tank),
+                });
+            }
+        }
+    }
+
+    for (i, (player_number, tank)) in tanks.iter().enumerate() {
+        for (other_player_number, other_tank) in tanks.iter().skip(i + 1) {
+            let distance = tank
+                .x
+                .abs_diff(other_tank.x)
+                .max(tank.y.abs_diff(other_tank.y));
+
+            if distance < MIN_SPAWN_DISTANCE {
+                warnings.push(Warning {
+                    message: format!(
+                        "Players {} and {} too close",
+                        player_number + 1,
+                        other_player_number + 1
+                    ),
+                    cells: tank_cells(tank)
+                        .into_iter()
+                        .chain(tank_cells(other_tank))
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    let blocks_out_of_bounds: Vec<(u16, u16)> = level
+        .blocks
+        .iter()
+        .filter(|block| !level.contains(block.x, block.y))
+        .map(|block| (block.x, block.y))
+        .collect();
+
+    if !blocks_out_of_bounds.is_empty() {
+        warnings.push(Warning {
+            message: format!("{} blocks out of bounds", blocks_out_of_bounds.len()),
+            cells: blocks_out_of_bounds,
+        });
+    }
+
+    let mut spawns_out_of_bounds = 0;
+    let mut spawn_cells_out_of_bounds = vec![];
+
+    for (i, spawn) in level.spawns.iter().enumerate() {
+        let spawned_tank = Tank {
+            x: spawn.x,
+            y: spawn.y,
+            direction: spawn.direction,
+        };
+
+        if !level.fits_tank(spawn.x, spawn.y) {
+            spawns_out_of_bounds += 1;
+            spawn_cells_out_of_bounds.extend(tank_cells(&spawned_tank));
+            continue;
+        }
+
+        // Spawns only have to lead to the starting positions, if there are any
+        if tanks.is_empty() {
+            continue;
+        }
+
+        let reachable = reachable_positions(&grid, &spawned_tank);
+
+        if !tanks
+            .iter()
+            .any(|(_, tank)| reachable.contains(&(tank.x, tank.y)))
+        {
+            warnings.push(Warning {
+                message: format!("Spawn {} is walled in", i + 1),
+                cells: tank_cells(&spawned_tank),
+            });
+        }
+    }
+
+    if spawns_out_of_bounds > 0 {
+        warnings.push(Warning {
+            message: format!("{} spawns out of bounds", spawns_out_of_bounds),
+            cells: spawn_cells_out_of_bounds,
+        });
+    }
+
+    for (player_number, tank) in tanks.iter() {
+        if !level.fits_tank(tank.x, tank.y) {
+            warnings.push(Warning {
+                message: format!("Player {} out of bounds", player_number + 1),
+                cells: tank_cells(tank),
+            });
+        }
+    }
+
+    warnings
+}
+
+// Also the ones past the edge of the map, for tanks out of bounds
+fn tank_cells(tank: &Tank) -> Vec<(u16, u16)> {
+    let mut cells = vec![];
+
+    for y in tank.y..tank.y + TANK_SIZE {
+        for x in tank.x..tank.x + TANK_SIZE {
+            cells.push((x, y));
+        }
+    }
+
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::level::{
+        block::{Block, BlockType, BlockVariant},
+        tank::{Direction, Spawn},
+    };
+
+    fn tank(x: u16, y: u16) -> Option<Tank> {
+        Some(Tank {
+            x,
+            y,
+            direction: Direction::Up,
+        })
+    }
+
+    fn block(x: u16, y: u16) -> Block {
+        Block {
+            x,
+            y,
+            block_type: BlockType::Concrete,
+            block_variant: BlockVariant::LeftTop,
+        }
+    }
+
+    fn warning<'a>(warnings: &'a [Warning], message: &str) -> &'a Warning {
+        warnings
+            .iter()
+            .find(|warning| warning.message.starts_with(message))
+            .unwrap_or_else(|| panic!("No warning starting with {}", message))
+    }
+
+    #[test]
+    fn every_warning_highlights_its_cells() {
+        let mut level = Level::new();
+        assert!(warning(&validate(&level), "Only 0").cells.is_empty());
+
+        level.tanks[0] = tank(0, 0);
+        assert_eq!(warning(&validate(&level), "Only 1").cells.len(), 16);
+
+        // Tanks too close to each other, with a wall between them
+        level.tanks[1] = tank(6, 0);
+        for y in 0..TANK_SIZE + 1 {
+            level.set_block(block(4, y));
+            level.set_block(block(5, y));
+        }
+        for x in 0..6 {
+            level.set_block(block(x, TANK_SIZE));
+        }
+
+        // Beyond the edge of the map, as in a file made for a larger map
+        level.set_block(block(60, 0));
+        level.spawns.push(Spawn {
+            x: 48,
+            y: 48,
+            direction: Direction::Up,
+            player_number: None,
+        });
+        level.tanks[2] = tank(47, 20);
+
+        let warnings = validate(&level);
+        assert!(!warning(&warnings, "Player 1 is walled in").cells.is_empty());
+        assert!(!warning(&warnings, "Players 1 and 2 too close")
+            .cells
+            .is_empty());
+        assert_eq!(
+            warning(&warnings, "1 blocks out of bounds").cells,
+            [(60, 0)]
+        );
+        assert!(warning(&warnings, "1 spawns out of bounds")
+            .cells
+            .contains(&(51, 51)));
+        assert!(warning(&warnings, "Player 3 out of bounds")
+            .cells
+            .contains(&(50, 20)));
+        assert!(warnings.iter().all(|warning| !warning.cells.is_empty()));
+    }
+}
@@ -1,12 +1,22 @@
+mod colors;
+mod dialog;
 mod drawing_utils;
+mod frame;
+mod glyphs;
 mod input;
 mod level;
 mod modes;
 mod options;
+mod paths;
+mod render_target;
+#[cfg(test)]
+mod snapshot_tests;
+mod text_field;
+mod theme;
 
 use self::{
     input::WindowState,
-    level::{LEVEL_MAP_WIDTH, LEVEL_SIZE},
+    level::{MAP_VIEW_SIZE, MAP_VIEW_WIDTH},
     modes::SIDEBAR_WIDTH,
 };
 use crossterm::{
@@ -21,50 +31,43 @@ use device_query::Keycode;
 use input::Input;
 use modes::{menu::Menu, Mode};
 use options::Options;
-use std::{
-    io::{Stdout, Write},
-    thread::sleep,
-    time::Instant,
-};
+use render_target::{RenderTarget, Terminal};
+use std::{io::Stdout, thread::sleep, time::Instant};
 
-const MIN_WIDTH: u16 = LEVEL_MAP_WIDTH + SIDEBAR_WIDTH;
-const MIN_HEIGHT: u16 = LEVEL_SIZE;
+// Size of the fixed layouts of the menus, also taken by the editor when the window is large enough
+const MIN_WIDTH: u16 = MAP_VIEW_WIDTH + SIDEBAR_WIDTH;
+const MIN_HEIGHT: u16 = MAP_VIEW_SIZE;
 
 pub struct Game {
-    stdout: Stdout,
+    terminal: Terminal<Stdout>,
     mode: Box<dyn Mode>,
     options: Options,
     input: Input,
-    horizontal_margin: u16,
-    vertical_margin: u16,
-    last_window_state: WindowState,
+    cursor_shown: bool, // Only while the window is too small
+    ctrl_c_pressed: bool,
 }
 
 impl Game {
     pub fn new(stdout: Stdout) -> Result<Self> {
         Ok(Game {
-            stdout,
+            terminal: Terminal::new(stdout),
             mode: Box::new(Menu::new()),
-            options: Options::new(),
+            options: Options::load(),
             input: Input::new()?,
-            horizontal_margin: 0,
-            vertical_margin: 0,
-            last_window_state: WindowState {
-                width: 0,
-                height: 0,
-            },
+            cursor_shown: false,
+            ctrl_c_pressed: false,
         })
     }
 
     pub fn init(&mut self) -> Result<()> {
         enable_raw_mode()?;
-        execute!(self.stdout, EnableMouseCapture, cursor::Hide)?;
+        execute!(self.terminal.output, EnableMouseCapture, cursor::Hide)?;
         Ok(())
     }
 
     pub fn uninit(&mut self) -> Result<()> {
         execute!(
-            self.stdout,
+            self.terminal.output,
             DisableMouseCapture,
             cursor::Show,
             SetBackgroundColor(Color::Reset),
@@ -86,57 +89,76 @@ impl Game {
             let delta_time = current_time - previous_time;
 
             let input_state = self.input.get_state();
+            self.terminal.resize(
+                input_state.window_state.width,
+                input_state.window_state.height,
+            );
             let ctrl_c = input_state.keyboard_state.contains(&Keycode::LControl)
                 && input_state.keyboard_state.contains(&Keycode::C);
 
-            if ctrl_c {
+            // React only to the moment of pressing, as the keys stay pressed for many frames
+            if ctrl_c && !self.ctrl_c_pressed && self.mode.request_quit() {
+                break;
+            }
+            self.ctrl_c_pressed = ctrl_c;
+
+            if self.mode.wants_to_quit() {
                 break;
             }
 
-            let window_too_small = input_state.window_state.height < MIN_HEIGHT
-                || input_state.window_state.width < MIN_WIDTH;
+            let window_state = &input_state.window_state;
+            let (min_width, min_height) = self.mode.min_window_size(window_state, &self.options);
+            let window_too_small =
+                window_state.width < min_width || window_state.height < min_height;
+
+            if window_too_small != self.cursor_shown {
+                self.cursor_shown = window_too_small;
+
+                if window_too_small {
+                    queue!(self.terminal.output, cursor::Show)?;
+                } else {
+                    queue!(self.terminal.output, cursor::Hide)?;
+                }
+            }
 
             if window_too_small {
-                self.print_window_size_information(input_state.window_state)?;
+                self.print_window_size_information(window_state, min_width, min_height)?;
             } else {
-                let mut refresh = false;
-                let window_resized = self.last_window_state.width != input_state.window_state.width
-                    || self.last_window_state.height != input_state.window_state.height;
-
-                if window_resized {
-                    refresh = true;
-                    self.horizontal_margin = (input_state.window_state.width - MIN_WIDTH) / 2;
-                    self.vertical_margin = (input_state.window_state.height - MIN_HEIGHT) / 2;
-                    self.last_window_state = input_state.window_state.clone();
-                    queue!(
-                        self.stdout,
-                        SetBackgroundColor(Color::DarkBlue),
-                        Clear(ClearType::All),
-                        cursor::Hide
-                    )?;
-                }
+                let (width, height) = self.mode.screen_size(window_state, &self.options);
+                let horizontal_margin = window_state.width.saturating_sub(width) / 2;
+                let vertical_margin = window_state.height.saturating_sub(height) / 2;
+                let margin_color = self.options.theme.ui.margin;
+                queue!(
+                    self.terminal,
+                    SetBackgroundColor(margin_color),
+                    Clear(ClearType::All)
+                )?;
 
                 let new_mode = self.mode.draw(
-                    &mut self.stdout,
+                    &mut self.terminal,
                     delta_time,
-                    self.horizontal_margin,
-                    self.vertical_margin,
-                    refresh,
+                    horizontal_margin,
+                    vertical_margin,
                     &input_state,
                     &self.options,
                 )?;
+                let saved_options = self.options.saved();
+                self.mode.apply_options(&mut self.options);
+
+                // Saving is best effort, the options still apply to this run
+                if self.options.saved() != saved_options {
+                    let _ = self.options.save();
+                }
 
                 if let Some(new_mode) = new_mode {
                     self.mode = new_mode;
-                    // Trigger a refresh
-                    self.last_window_state = WindowState {
-                        width: 0,
-                        height: 0,
-                    };
                 }
             }
 
-            self.stdout.flush()?;
+            self.terminal
+                .set_color_support(self.options.color_support());
+            self.terminal.set_ascii_only(self.options.ascii_only);
+            self.terminal.present()?;
 
             let desired_time = current_time + self.options.interval;
             let now = Instant::now();
@@ -148,10 +170,14 @@ impl Game {
         Ok(())
     }
 
-    fn print_window_size_information(&mut self, window_state: WindowState) -> Result<()> {
+    fn print_window_size_information(
+        &mut self,
+        window_state: &WindowState,
+        min_width: u16,
+        min_height: u16,
+    ) -> Result<()> {
         queue!(
-            self.stdout,
-            cursor::Show,
+            self.terminal,
             SetBackgroundColor(Color::Black),
             SetForegroundColor(Color::White),
             Clear(ClearType::All),
@@ -159,7 +185,7 @@ impl Game {
             SetAttribute(Attribute::Encircled),
             Print(format!(
                 "Window must be at least {}x{}. Your window is: {}x{}",
-                MIN_WIDTH, MIN_HEIGHT, window_state.width, window_state.height
+                min_width, min_height, window_state.width, window_state.height
             )),
         )?;
         Ok(())
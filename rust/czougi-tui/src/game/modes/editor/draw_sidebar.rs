@@ -1,93 +1,663 @@
-use crate::game::drawing_utils::draw_multi_line_text;
+use super::reachability::Reachability;
+use crate::game::drawing_utils::{draw_multi_line_text, wrap_text};
+use crate::game::input::MouseState;
 use crate::game::level::block::{draw_full_block, BlockType};
 use crate::game::level::tank::{draw_tank, Direction};
-use crate::game::level::LEVEL_SIZE;
+use crate::game::level::{
+    statistics::{free_cells, map_cells, LevelStatistics},
+    validation::Warning,
+    Level,
+};
 use crate::game::modes::SIDEBAR_WIDTH;
+use crate::game::render_target::RenderTarget;
+use crate::game::text_field::{glyph_width, TextField};
+use crate::game::theme::Theme;
 use crossterm::style::{Attribute, Color, SetAttribute, SetBackgroundColor, SetForegroundColor};
 use crossterm::{cursor, queue, style::Print, Result};
-use std::io::Stdout;
 
 const ERASER: [&str; 4] = ["▄▄    ▄▄", " ▀▀▄▄▀▀", " ▄▄▀▀▄▄", "▀▀    ▀▀"];
+const PICKER: [&str; 4] = ["     ▄█▌", "   ▄██▀ ", " ▄█▀    ", "▀▀      "];
 
-pub(super) fn draw_sidebar(stdout: &mut Stdout, x: u16, y: u16) -> Result<()> {
-    queue!(stdout, SetBackgroundColor(Color::White))?;
+// Whole sidebar, which is split into pages in shorter windows
+pub(super) const SIDEBAR_HEIGHT: u16 = 50;
+pub(super) const PAGED_SIDEBAR_HEIGHT: u16 = 24;
+const PAGE_TABS_ROW: u16 = PAGED_SIDEBAR_HEIGHT - 1;
+const PAGE_TAB_WIDTH: u16 = 8;
+// Centered in the sidebar
+pub(super) const SAVE_BUTTON_X: u16 = 7;
 
-    for row in y..LEVEL_SIZE + y {
+// Width of the text lines in the sidebar, leaving one column of padding on each side
+pub(super) const METADATA_WIDTH: u16 = SIDEBAR_WIDTH - 2;
+// Rows below are relative to the top of their section
+pub(super) const TITLE_ROW: u16 = 1;
+pub(super) const AUTHOR_ROW: u16 = 2;
+pub(super) const DESCRIPTION_ROW: u16 = 3;
+pub(super) const DESCRIPTION_LINES: u16 = 3;
+pub(super) const STATISTICS_ROW: u16 = 6;
+pub(super) const MAP_SIZE_ROW: u16 = 9;
+// Columns of the arrows changing the map size, relative to the sidebar
+pub(super) const WIDTH_ARROWS: (u16, u16) = (7, 13);
+pub(super) const HEIGHT_ARROWS: (u16, u16) = (17, 23);
+pub(super) const SPAWN_HINT_ROW: u16 = 4;
+pub(super) const SHARED_SPAWN_ROW: u16 = 9;
+pub(super) const REPLACE_TOOL_ROW: u16 = 5;
+pub(super) const WARNINGS_LINES: u16 = 3;
+
+#[derive(Copy, Clone, PartialEq)]
+pub(super) enum SidebarPage {
+    Tools,
+    Tanks,
+    Level,
+}
+
+impl SidebarPage {
+    const ALL: [SidebarPage; 3] = [SidebarPage::Tools, SidebarPage::Tanks, SidebarPage::Level];
+
+    fn name(self) -> &'static str {
+        match self {
+            SidebarPage::Tools => "Tools",
+            SidebarPage::Tanks => "Tanks",
+            SidebarPage::Level => "Level",
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub(super) enum SidebarSection {
+    Level, // Metadata, statistics and size of the map, below the back arrow and the save status
+    Blocks,
+    Tanks,
+    Tools, // Eraser, picker and replace tool
+    Reachability,
+    Buttons,
+    Warnings,
+}
+
+// Whole sidebar in tall windows, or only the sections of the chosen page with tabs for switching
+// pages at the bottom
+#[derive(Copy, Clone)]
+pub(super) struct SidebarLayout {
+    pub paged: bool,
+    pub page: SidebarPage,
+}
+
+impl SidebarLayout {
+    pub fn new() -> Self {
+        SidebarLayout {
+            paged: false,
+            page: SidebarPage::Tools,
+        }
+    }
+
+    pub fn height(&self) -> u16 {
+        if self.paged {
+            PAGED_SIDEBAR_HEIGHT
+        } else {
+            SIDEBAR_HEIGHT
+        }
+    }
+
+    // Top row of the section relative to the top of the sidebar, None if it is on another page
+    pub fn section_row(&self, section: SidebarSection) -> Option<u16> {
+        let (page, paged_row, row) = match section {
+            SidebarSection::Level => (SidebarPage::Level, 0, 0),
+            SidebarSection::Blocks => (SidebarPage::Tools, 2, 10),
+            SidebarSection::Tanks => (SidebarPage::Tanks, 2, 20),
+            SidebarSection::Tools => (SidebarPage::Tools, 12, 30),
+            SidebarSection::Reachability => (SidebarPage::Tanks, 13, 37),
+            SidebarSection::Buttons => (SidebarPage::Level, 11, 40),
+            SidebarSection::Warnings => (SidebarPage::Level, 18, 46),
+        };
+
+        match (self.paged, page == self.page) {
+            (false, _) => Some(row),
+            (true, true) => Some(paged_row),
+            (true, false) => None,
+        }
+    }
+
+    // Page of the tab under the mouse
+    pub fn clicked_page(&self, mouse_state: &MouseState, x: u16, y: u16) -> Option<SidebarPage> {
+        if !self.paged {
+            return None;
+        }
+
+        SidebarPage::ALL.into_iter().zip(0..).find_map(|(page, i)| {
+            mouse_state
+                .is_clicked(
+                    x + 1 + i * PAGE_TAB_WIDTH,
+                    y + PAGE_TABS_ROW,
+                    PAGE_TAB_WIDTH - 2,
+                    0,
+                )
+                .then_some(page)
+        })
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub(super) enum MetadataField {
+    Title,
+    Author,
+    Description,
+}
+
+// Background of the given height with the back arrow, and the page tabs of the paged layout
+pub(super) fn draw_sidebar(
+    target: &mut impl RenderTarget,
+    x: u16,
+    y: u16,
+    height: u16,
+    layout: &SidebarLayout,
+) -> Result<()> {
+    queue!(target, SetBackgroundColor(Color::White))?;
+
+    for row in y..height + y {
         queue!(
-            stdout,
+            target,
             cursor::MoveTo(x, row),
-            Print("                         "),
+            Print(" ".repeat(SIDEBAR_WIDTH as usize)),
         )?;
     }
 
-    let title = String::from("Place title here");
+    queue!(
+        target,
+        SetForegroundColor(Color::Red),
+        cursor::MoveTo(x + 1, y),
+        Print("◄--"),
+    )?;
 
+    if !layout.paged {
+        return Ok(());
+    }
+
+    for (page, i) in SidebarPage::ALL.into_iter().zip(0..) {
+        let (background, foreground) = if page == layout.page {
+            (Color::Black, Color::White)
+        } else {
+            (Color::Grey, Color::Black)
+        };
+
+        queue!(
+            target,
+            SetBackgroundColor(background),
+            SetForegroundColor(foreground),
+            cursor::MoveTo(x + 1 + i * PAGE_TAB_WIDTH, y + PAGE_TABS_ROW),
+            Print(format!(" {} ", page.name())),
+        )?;
+    }
+
+    Ok(())
+}
+
+pub(super) fn draw_block_tools(
+    target: &mut impl RenderTarget,
+    theme: &Theme,
+    x: u16,
+    y: u16,
+) -> Result<()> {
+    let block_types = [
+        (BlockType::Brick, 3, 0),
+        (BlockType::Concrete, 14, 0),
+        (BlockType::Water, 3, 5),
+        (BlockType::Leaves, 14, 5),
+    ];
+
+    for (block_type, column, row) in block_types {
+        draw_full_block(target, theme, block_type, x + column, y + row)?;
+        draw_full_block(target, theme, block_type, x + column + 4, y + row)?;
+        draw_full_block(target, theme, block_type, x + column, y + row + 2)?;
+        draw_full_block(target, theme, block_type, x + column + 4, y + row + 2)?;
+    }
+
+    Ok(())
+}
+
+pub(super) fn draw_tank_tools(
+    target: &mut impl RenderTarget,
+    theme: &Theme,
+    x: u16,
+    y: u16,
+) -> Result<()> {
+    queue!(target, SetBackgroundColor(Color::White))?;
+    draw_tank(target, theme, x + 3, y, 0, Direction::Up)?;
+    draw_tank(target, theme, x + 14, y, 1, Direction::Up)?;
+    draw_tank(target, theme, x + 3, y + 5, 2, Direction::Up)?;
+    draw_tank(target, theme, x + 14, y + 5, 3, Direction::Up)?;
+
+    queue!(target, SetForegroundColor(Color::DarkGrey))?;
+    draw_centered(target, "2nd click: extra spawn", x, y + SPAWN_HINT_ROW)?;
+    queue!(target, SetForegroundColor(Color::Black))?;
+    draw_centered(target, "[ shared spawn ]", x, y + SHARED_SPAWN_ROW)?;
+
+    Ok(())
+}
+
+// Eraser and picker, above the replace tool
+pub(super) fn draw_other_tools(target: &mut impl RenderTarget, x: u16, y: u16) -> Result<()> {
     queue!(
-        stdout,
-        cursor::MoveTo(x + (SIDEBAR_WIDTH - title.len() as u16) / 2, y + 3),
-        SetForegroundColor(Color::Black),
+        target,
+        SetBackgroundColor(Color::White),
+        SetForegroundColor(Color::Rgb { r: 255, g: 0, b: 0 }),
+        SetAttribute(Attribute::Bold)
+    )?;
+    draw_multi_line_text(target, ERASER.iter(), x + 3, y)?;
+
+    queue!(target, SetForegroundColor(Color::DarkMagenta))?;
+    draw_multi_line_text(target, PICKER.iter(), x + 14, y)?;
+    queue!(target, SetAttribute(Attribute::NoBold))?;
+
+    Ok(())
+}
+
+// Levels can only be saved from the editor, there is no game mode to play them in yet
+pub(super) fn draw_save_button(target: &mut impl RenderTarget, x: u16, y: u16) -> Result<()> {
+    queue!(
+        target,
+        SetBackgroundColor(Color::White),
+        SetForegroundColor(Color::Blue),
         SetAttribute(Attribute::Bold),
-        Print(title),
+        cursor::MoveTo(x + SAVE_BUTTON_X, y),
+        Print("┌─────────┐"),
+        cursor::MoveTo(x + SAVE_BUTTON_X, y + 1),
+        Print("│  Save   │"),
+        cursor::MoveTo(x + SAVE_BUTTON_X, y + 2),
+        Print("└─────────┘"),
+        SetAttribute(Attribute::NoBold),
+    )?;
+
+    Ok(())
+}
+
+pub(super) fn draw_level_metadata(
+    target: &mut impl RenderTarget,
+    x: u16,
+    y: u16,
+    level: &Level,
+    edited_field: Option<&(MetadataField, TextField)>,
+) -> Result<()> {
+    queue!(target, SetBackgroundColor(Color::White))?;
+
+    for row in [TITLE_ROW, AUTHOR_ROW]
+        .into_iter()
+        .chain(DESCRIPTION_ROW..DESCRIPTION_ROW + DESCRIPTION_LINES)
+    {
+        queue!(
+            target,
+            cursor::MoveTo(x + 1, y + row),
+            Print(" ".repeat(METADATA_WIDTH as usize)),
+        )?;
+    }
+
+    let edited = |field| {
+        edited_field
+            .filter(|(f, _)| *f == field)
+            .map(|(_, text_field)| text_field)
+    };
+
+    queue!(
+        target,
+        SetForegroundColor(Color::Black),
+        SetAttribute(Attribute::Bold)
+    )?;
+
+    match edited(MetadataField::Title) {
+        Some(text_field) => text_field.draw(target, x + 1, y + TITLE_ROW, METADATA_WIDTH)?,
+        None => draw_centered(target, &level.title, x, y + TITLE_ROW)?,
+    }
+
+    queue!(
+        target,
         SetAttribute(Attribute::Reset),
+        SetBackgroundColor(Color::White)
     )?;
 
-    draw_full_block(stdout, BlockType::Brick, x + 3, y + 10)?;
-    draw_full_block(stdout, BlockType::Brick, x + 7, y + 10)?;
-    draw_full_block(stdout, BlockType::Brick, x + 3, y + 12)?;
-    draw_full_block(stdout, BlockType::Brick, x + 7, y + 12)?;
+    match edited(MetadataField::Author) {
+        Some(text_field) => text_field.draw(target, x + 1, y + AUTHOR_ROW, METADATA_WIDTH)?,
+        None if level.author.is_empty() => {
+            queue!(target, SetForegroundColor(Color::DarkGrey))?;
+            draw_centered(target, "(no author)", x, y + AUTHOR_ROW)?;
+        }
+        None => {
+            queue!(target, SetForegroundColor(Color::Black))?;
+            draw_centered(target, &format!("by {}", level.author), x, y + AUTHOR_ROW)?;
+        }
+    }
 
-    draw_full_block(stdout, BlockType::Concrete, x + 14, y + 10)?;
-    draw_full_block(stdout, BlockType::Concrete, x + 18, y + 10)?;
-    draw_full_block(stdout, BlockType::Concrete, x + 14, y + 12)?;
-    draw_full_block(stdout, BlockType::Concrete, x + 18, y + 12)?;
+    match edited(MetadataField::Description) {
+        Some(text_field) => {
+            queue!(target, SetForegroundColor(Color::Black))?;
+            text_field.draw(target, x + 1, y + DESCRIPTION_ROW, METADATA_WIDTH)?;
+        }
+        None if level.description.is_empty() => {
+            queue!(target, SetForegroundColor(Color::DarkGrey))?;
+            draw_centered(target, "(no description)", x, y + DESCRIPTION_ROW)?;
+        }
+        None => {
+            queue!(target, SetForegroundColor(Color::Black))?;
+            let lines = wrap_text(&level.description, METADATA_WIDTH as usize);
 
-    draw_full_block(stdout, BlockType::Water, x + 3, y + 15)?;
-    draw_full_block(stdout, BlockType::Water, x + 7, y + 15)?;
-    draw_full_block(stdout, BlockType::Water, x + 3, y + 17)?;
-    draw_full_block(stdout, BlockType::Water, x + 7, y + 17)?;
+            for (i, line) in lines.iter().take(DESCRIPTION_LINES as usize).enumerate() {
+                queue!(
+                    target,
+                    cursor::MoveTo(x + 1, y + DESCRIPTION_ROW + i as u16),
+                    Print(line),
+                )?;
+            }
+        }
+    }
 
-    draw_full_block(stdout, BlockType::Leaves, x + 14, y + 15)?;
-    draw_full_block(stdout, BlockType::Leaves, x + 18, y + 15)?;
-    draw_full_block(stdout, BlockType::Leaves, x + 14, y + 17)?;
-    draw_full_block(stdout, BlockType::Leaves, x + 18, y + 17)?;
+    Ok(())
+}
 
-    queue!(stdout, SetBackgroundColor(Color::White))?;
-    draw_tank(stdout, x + 3, y + 20, 0, Direction::Up)?;
-    draw_tank(stdout, x + 14, y + 20, 1, Direction::Up)?;
-    draw_tank(stdout, x + 3, y + 25, 2, Direction::Up)?;
-    draw_tank(stdout, x + 14, y + 25, 3, Direction::Up)?;
+// Prints a single line centered in the sidebar, cutting it if it is too long
+fn draw_centered(target: &mut impl RenderTarget, text: &str, x: u16, y: u16) -> Result<()> {
+    let mut columns = 0;
+    let text: String = text
+        .chars()
+        .take_while(|c| {
+            columns += glyph_width(*c) as u16;
+            columns <= METADATA_WIDTH
+        })
+        .collect();
+    let length = text.chars().map(glyph_width).sum::<usize>() as u16;
 
     queue!(
-        stdout,
-        SetForegroundColor(Color::Rgb { r: 255, g: 0, b: 0 }),
-        SetAttribute(Attribute::Bold)
+        target,
+        cursor::MoveTo(x + (SIDEBAR_WIDTH - length) / 2, y),
+        Print(text),
+    )?;
+
+    Ok(())
+}
+
+pub(super) fn draw_replace_tool(
+    target: &mut impl RenderTarget,
+    theme: &Theme,
+    x: u16,
+    y: u16,
+    replaced_block_type: BlockType,
+    new_block_type: BlockType,
+) -> Result<()> {
+    draw_full_block(
+        target,
+        theme,
+        replaced_block_type,
+        x + 2,
+        y + REPLACE_TOOL_ROW,
+    )?;
+    draw_full_block(
+        target,
+        theme,
+        replaced_block_type,
+        x + 6,
+        y + REPLACE_TOOL_ROW,
     )?;
-    draw_multi_line_text(stdout, ERASER.iter(), x + 9, y + 30)?;
 
-    let buttons = [
-        (" Play", Color::DarkGreen),
-        (" Save", Color::Blue),
-        ("Discard", Color::Black),
-        ("Delete", Color::Red),
+    draw_full_block(target, theme, new_block_type, x + 15, y + REPLACE_TOOL_ROW)?;
+    draw_full_block(target, theme, new_block_type, x + 19, y + REPLACE_TOOL_ROW)?;
+
+    queue!(
+        target,
+        SetBackgroundColor(Color::White),
+        SetForegroundColor(Color::Black),
+        cursor::MoveTo(x + 11, y + REPLACE_TOOL_ROW),
+        Print("══►"),
+        cursor::MoveTo(x + 11, y + REPLACE_TOOL_ROW + 1),
+        Print("all"),
+    )?;
+
+    Ok(())
+}
+
+pub(super) fn draw_warnings(
+    target: &mut impl RenderTarget,
+    x: u16,
+    y: u16,
+    warnings: &[Warning],
+    highlighted_warning: Option<usize>,
+) -> Result<()> {
+    queue!(target, SetBackgroundColor(Color::White))?;
+
+    for row in 0..WARNINGS_LINES + 1 {
+        queue!(
+            target,
+            cursor::MoveTo(x + 1, y + row),
+            Print(" ".repeat(METADATA_WIDTH as usize)),
+        )?;
+    }
+
+    if warnings.is_empty() {
+        queue!(
+            target,
+            SetForegroundColor(Color::DarkGreen),
+            cursor::MoveTo(x + 1, y),
+            Print("✔ Level is valid"),
+        )?;
+        return Ok(());
+    }
+
+    queue!(
+        target,
+        SetForegroundColor(Color::DarkRed),
+        SetAttribute(Attribute::Bold),
+        cursor::MoveTo(x + 1, y),
+        Print(format!("⚠ Warnings: {}", warnings.len())),
+        SetAttribute(Attribute::NoBold),
+    )?;
+
+    for (i, warning) in warnings.iter().take(WARNINGS_LINES as usize).enumerate() {
+        if highlighted_warning == Some(i) {
+            queue!(
+                target,
+                SetBackgroundColor(Color::DarkRed),
+                SetForegroundColor(Color::White)
+            )?;
+        } else {
+            queue!(
+                target,
+                SetBackgroundColor(Color::White),
+                SetForegroundColor(Color::Black)
+            )?;
+        }
+
+        let message: String = warning
+            .message
+            .chars()
+            .take(METADATA_WIDTH as usize - 2)
+            .collect();
+
+        queue!(
+            target,
+            cursor::MoveTo(x + 1, y + 1 + i as u16),
+            Print(format!("• {}", message)),
+        )?;
+    }
+
+    Ok(())
+}
+
+pub(super) fn draw_save_status(
+    target: &mut impl RenderTarget,
+    x: u16,
+    y: u16,
+    unsaved_changes: bool,
+    save_failed: bool,
+) -> Result<()> {
+    let (color, text) = if save_failed {
+        (Color::Red, "save failed")
+    } else if unsaved_changes {
+        (Color::DarkRed, "  unsaved ●")
+    } else {
+        (Color::DarkGreen, "    saved ✔")
+    };
+
+    queue!(
+        target,
+        SetBackgroundColor(Color::White),
+        SetForegroundColor(color),
+        cursor::MoveTo(x + SIDEBAR_WIDTH - 12, y),
+        Print(text),
+    )?;
+
+    Ok(())
+}
+
+pub(super) fn draw_reachability_panel(
+    target: &mut impl RenderTarget,
+    theme: &Theme,
+    x: u16,
+    y: u16,
+    reachability: Option<&Reachability>,
+) -> Result<()> {
+    queue!(target, SetBackgroundColor(Color::White))?;
+
+    for row in 0..3 {
+        queue!(
+            target,
+            cursor::MoveTo(x + 1, y + row),
+            Print(" ".repeat(METADATA_WIDTH as usize)),
+        )?;
+    }
+
+    queue!(
+        target,
+        SetForegroundColor(Color::Black),
+        cursor::MoveTo(x + 1, y),
+        Print("Reachability: "),
+    )?;
+
+    let (player_number, spawn_path_costs) = match reachability {
+        Some(reachability) => (reachability.player_number, &reachability.spawn_path_costs),
+        None => {
+            queue!(target, Print("off"))?;
+            return Ok(());
+        }
+    };
+
+    queue!(
+        target,
+        SetForegroundColor(theme.player_color(player_number)),
+        Print(format!("player {}", player_number + 1)),
+        SetForegroundColor(Color::Black),
+    )?;
+
+    // Three pairs per line, e.g. "1-2: 42 1-3:  - 1-4:>1k"
+    for (i, (player_number, other_player_number, cost)) in spawn_path_costs.iter().enumerate() {
+        let cost = match cost {
+            Some(cost) if *cost > 999 => String::from(">1k"),
+            Some(cost) => cost.to_string(),
+            None => String::from("-"),
+        };
+        let i = i as u16;
+
+        queue!(
+            target,
+            cursor::MoveTo(x + 1 + (i % 3) * 8, y + 1 + i / 3),
+            Print(format!(
+                "{}-{}:{:>3}",
+                player_number + 1,
+                other_player_number + 1,
+                cost
+            )),
+        )?;
+    }
+
+    Ok(())
+}
+
+// Map size with arrows for shrinking and growing it, e.g. "Size ◄  50 ► x ◄  50 ►"
+pub(super) fn draw_map_size(
+    target: &mut impl RenderTarget,
+    x: u16,
+    y: u16,
+    level: &Level,
+) -> Result<()> {
+    queue!(
+        target,
+        SetBackgroundColor(Color::White),
+        SetForegroundColor(Color::Black),
+        cursor::MoveTo(x + 1, y + MAP_SIZE_ROW),
+        Print("Size"),
+    )?;
+
+    for ((left_arrow, right_arrow), size) in
+        [(WIDTH_ARROWS, level.width), (HEIGHT_ARROWS, level.height)]
+    {
+        queue!(
+            target,
+            SetForegroundColor(Color::DarkBlue),
+            cursor::MoveTo(x + left_arrow, y + MAP_SIZE_ROW),
+            Print("◄"),
+            SetForegroundColor(Color::Black),
+            Print(format!("{:>4} ", size)),
+            SetForegroundColor(Color::DarkBlue),
+            cursor::MoveTo(x + right_arrow, y + MAP_SIZE_ROW),
+            Print("►"),
+        )?;
+    }
+
+    queue!(
+        target,
+        SetForegroundColor(Color::Black),
+        cursor::MoveTo(x + 15, y + MAP_SIZE_ROW),
+        Print("x"),
+    )?;
+
+    Ok(())
+}
+
+pub(super) fn draw_statistics(
+    target: &mut impl RenderTarget,
+    theme: &Theme,
+    x: u16,
+    y: u16,
+    level: &Level,
+    statistics: &LevelStatistics,
+) -> Result<()> {
+    let block_types = [
+        BlockType::Brick,
+        BlockType::Concrete,
+        BlockType::Water,
+        BlockType::Leaves,
     ];
 
-    for (i, (text, color)) in buttons.iter().enumerate() {
+    // Two block types per line, e.g. "   120  4%    16  0%"
+    for (i, block_type) in block_types.into_iter().enumerate() {
         let i = i as u16;
-        let x = x + 1 + (i % 2) * 12;
-        let y = y + 40 + (i / 2) * 3;
+        let count = statistics.block_count(block_type);
+
         queue!(
-            stdout,
-            SetForegroundColor(*color),
-            cursor::MoveTo(x, y),
-            Print("┌─────────┐"),
-            cursor::MoveTo(x, y + 1),
-            Print("│         │"),
-            cursor::MoveTo(x, y + 2),
-            Print("└─────────┘"),
-            cursor::MoveTo(x + 2, y + 1),
-            Print(text),
+            target,
+            cursor::MoveTo(x + 1 + (i % 2) * 12, y + STATISTICS_ROW + i / 2),
+            SetBackgroundColor(theme.block(block_type).background),
+            Print("  "),
+            SetBackgroundColor(Color::White),
+            SetForegroundColor(Color::Black),
+            Print(format!(
+                " {:>4} {:>2}%",
+                count,
+                count * 100 / map_cells(level)
+            )),
         )?;
     }
 
+    let tanks = level.tanks.iter().flatten().count();
+    let free_area = free_cells(level) * 100 / map_cells(level);
+
+    queue!(
+        target,
+        cursor::MoveTo(x + 1, y + STATISTICS_ROW + 2),
+        Print(format!(
+            "{:<23}",
+            format!(
+                "T:{} Free:{}% Sym:{}%",
+                tanks,
+                free_area,
+                statistics.symmetry_score()
+            )
+        )),
+    )?;
+
     Ok(())
 }
@@ -1,51 +1,65 @@
-use std::io::Stdout;
-
-use crossterm::Result;
-
-use super::{tool::Tool, Editor};
+use super::{
+    draw_sidebar::{
+        MetadataField, SidebarSection, AUTHOR_ROW, DESCRIPTION_LINES, DESCRIPTION_ROW,
+        HEIGHT_ARROWS, MAP_SIZE_ROW, METADATA_WIDTH, REPLACE_TOOL_ROW, SAVE_BUTTON_X,
+        SHARED_SPAWN_ROW, TITLE_ROW, WARNINGS_LINES, WIDTH_ARROWS,
+    },
+    tool::Tool,
+    Editor,
+};
 use crate::game::{
     input::{ButtonState, MouseState},
     level::{
         block::{Block, BlockType, BlockVariant},
-        tank::{Direction, Tank, TANK_SIZE},
-        LEVEL_MAP_WIDTH, LEVEL_SIZE,
+        tank::{Direction, Spawn, Tank, TANK_SIZE},
+        tile::Tile,
     },
 };
 
 impl Editor {
     pub(super) fn handle_map_mouse_actions(
         &mut self,
-        stdout: &mut Stdout,
-        horizontal_margin: u16,
-        vertical_margin: u16,
         mouse_state: &MouseState,
         mouse_map_x: u16,
         mouse_map_y: u16,
-    ) -> Result<()> {
-        match mouse_state.left_button {
-            ButtonState::GettingPressed => {
-                if !matches!(self.tool, Tool::Tank(_, _))
-                    && mouse_map_x < LEVEL_SIZE
-                    && mouse_map_y < LEVEL_SIZE
-                {
-                    self.first_selection_corner = Some((mouse_map_x, mouse_map_y));
-                }
+    ) {
+        // Right button always erases, whatever tool is currently selected
+        let (tool, button_state) = if matches!(mouse_state.left_button, ButtonState::Released)
+            && !matches!(mouse_state.right_button, ButtonState::Released)
+        {
+            (Tool::Eraser, &mouse_state.right_button)
+        } else {
+            (self.tool, &mouse_state.left_button)
+        };
+
+        // Middle button picks the block under the cursor, like the picker tool
+        if matches!(mouse_state.middle_button, ButtonState::GettingReleased)
+            || (matches!(tool, Tool::Picker)
+                && matches!(button_state, ButtonState::GettingReleased))
+        {
+            if let Some(picked_tool) = Tool::pick(&self.level, mouse_map_x, mouse_map_y) {
+                self.tool = picked_tool;
+            }
+
+            return;
+        }
+
+        match button_state {
+            ButtonState::GettingPressed
+                if !matches!(tool, Tool::Tank(_, _) | Tool::Spawn(_, _) | Tool::Picker)
+                    && self.level.contains(mouse_map_x, mouse_map_y) =>
+            {
+                self.first_selection_corner = Some((mouse_map_x, mouse_map_y));
             }
             ButtonState::GettingReleased => {
-                if let Tool::Tank(player_number, direction) = self.tool {
-                    self.place_tank(
-                        stdout,
-                        horizontal_margin,
-                        vertical_margin,
-                        mouse_map_x,
-                        mouse_map_y,
-                        player_number,
-                        direction,
-                    )?;
+                if let Tool::Tank(player_number, direction) = tool {
+                    self.place_tank(mouse_map_x, mouse_map_y, player_number, direction);
+                } else if let Tool::Spawn(player_number, direction) = tool {
+                    self.place_spawn(mouse_map_x, mouse_map_y, player_number, direction);
                 } else if let Some((first_selection_corner_x, first_selection_corner_y)) =
                     self.first_selection_corner
                 {
-                    if mouse_map_x < LEVEL_SIZE && mouse_map_y < LEVEL_SIZE {
+                    if self.level.contains(mouse_map_x, mouse_map_y) {
                         let (left_top_x, right_bottom_x) = if first_selection_corner_x < mouse_map_x
                         {
                             (first_selection_corner_x, mouse_map_x)
@@ -60,7 +74,7 @@ impl Editor {
                             (mouse_map_y, first_selection_corner_y)
                         };
 
-                        match self.tool {
+                        match tool {
                             Tool::SmallBlock(block_type, block_variant) => self.place_block(
                                 left_top_x,
                                 left_top_y,
@@ -69,45 +83,30 @@ impl Editor {
                                 block_type,
                                 |_, _| block_variant,
                             ),
-                            Tool::FullBlock(block_type) => self.place_block(
+                            Tool::FullBlock(block_type) => self.place_tiles(
                                 left_top_x,
                                 left_top_y,
                                 right_bottom_x,
                                 right_bottom_y,
                                 block_type,
-                                |x, y| {
-                                    let is_left = (x - left_top_x) % 2 == 0;
-                                    let is_top = (y - left_top_y) % 2 == 0;
-
-                                    match (is_left, is_top) {
-                                        (true, true) => BlockVariant::LeftTop,
-                                        (true, false) => BlockVariant::LeftBottom,
-                                        (false, true) => BlockVariant::RightTop,
-                                        (false, false) => BlockVariant::RightBottom,
-                                    }
-                                },
                             ),
                             Tool::Eraser => self.erase(
-                                stdout,
-                                horizontal_margin,
-                                vertical_margin,
                                 mouse_map_x,
                                 mouse_map_y,
                                 first_selection_corner_x,
                                 first_selection_corner_y,
-                            )?,
+                            ),
+                            Tool::Replace(replaced_block_type, new_block_type) => self
+                                .replace_blocks(
+                                    left_top_x,
+                                    left_top_y,
+                                    right_bottom_x,
+                                    right_bottom_y,
+                                    replaced_block_type,
+                                    new_block_type,
+                                ),
                             _ => unreachable!(),
                         };
-
-                        self.level.draw(
-                            stdout,
-                            horizontal_margin,
-                            vertical_margin,
-                            left_top_x,
-                            left_top_y,
-                            right_bottom_x - left_top_x + 1,
-                            right_bottom_y - left_top_y + 1,
-                        )?;
                     }
                 }
 
@@ -115,92 +114,85 @@ impl Editor {
             }
             _ => {}
         }
-
-        Ok(())
     }
 
     fn place_tank(
         &mut self,
-        stdout: &mut Stdout,
-        horizontal_margin: u16,
-        vertical_margin: u16,
         mouse_map_x: u16,
         mouse_map_y: u16,
         player_number: u8,
         direction: Direction,
-    ) -> Result<()> {
-        let mut is_overlapping = false;
-
-        for block in self.level.blocks.iter() {
-            if block.x >= mouse_map_x
-                && block.x < mouse_map_x + TANK_SIZE
-                && block.y >= mouse_map_y
-                && block.y < mouse_map_y + TANK_SIZE
-            {
-                is_overlapping = true;
-                break;
-            }
-        }
-
-        if !is_overlapping {
-            for (t, p) in self.level.tanks.iter().zip(0..4) {
-                if p != player_number {
-                    if let Some(tank) = t {
-                        if tank.x + 3 >= mouse_map_x
-                            && tank.x <= mouse_map_x + 3
-                            && tank.y + 3 >= mouse_map_y
-                            && tank.y <= mouse_map_y + 3
-                        {
-                            is_overlapping = true;
-                            break;
-                        }
-                    }
-                }
-            }
-        }
+    ) {
+        let tank = Some(Tank {
+            x: mouse_map_x,
+            y: mouse_map_y,
+            direction,
+        });
 
-        if !is_overlapping
-            && mouse_map_x <= LEVEL_SIZE - TANK_SIZE
-            && mouse_map_y <= LEVEL_SIZE - TANK_SIZE
+        if self.can_place_tank(mouse_map_x, mouse_map_y, Some(player_number))
+            && self.level.tanks[player_number as usize] != tank
         {
-            let previous_position = if let Some(tank) = &self.level.tanks[player_number as usize] {
-                Some((tank.x, tank.y))
-            } else {
-                None
-            };
+            self.level.tanks[player_number as usize] = tank;
+            self.mark_level_changed();
+        }
+    }
 
-            self.level.tanks[player_number as usize] = Some(Tank {
+    fn place_spawn(
+        &mut self,
+        mouse_map_x: u16,
+        mouse_map_y: u16,
+        player_number: Option<u8>,
+        direction: Direction,
+    ) {
+        if self.can_place_tank(mouse_map_x, mouse_map_y, None) {
+            self.level.spawns.push(Spawn {
                 x: mouse_map_x,
                 y: mouse_map_y,
                 direction,
+                player_number,
             });
+            self.mark_level_changed();
+        }
+    }
 
-            // Remove previous tank graphics
-            if let Some((previous_x, previous_y)) = previous_position {
-                self.level.draw(
-                    stdout,
-                    horizontal_margin,
-                    vertical_margin,
-                    previous_x,
-                    previous_y,
-                    TANK_SIZE,
-                    TANK_SIZE,
-                )?;
-            }
-
-            // Draw new tank graphics
-            self.level.draw(
-                stdout,
-                horizontal_margin,
-                vertical_margin,
-                mouse_map_x,
-                mouse_map_y,
-                TANK_SIZE,
-                TANK_SIZE,
-            )?;
+    // Checks if a tank fits in the map without covering blocks, spawns or tanks other than the
+    // one of the moved player
+    fn can_place_tank(&self, x: u16, y: u16, moved_player: Option<u8>) -> bool {
+        if !self.level.fits_tank(x, y) {
+            return false;
         }
 
-        Ok(())
+        let overlaps = |other_x: u16, other_y: u16| {
+            other_x + TANK_SIZE > x
+                && other_x < x + TANK_SIZE
+                && other_y + TANK_SIZE > y
+                && other_y < y + TANK_SIZE
+        };
+
+        let covers_block = self
+            .level
+            .blocks
+            .iter_region(x, y, TANK_SIZE, TANK_SIZE)
+            .next()
+            .is_some();
+
+        let covers_tank = self
+            .level
+            .tanks
+            .iter()
+            .zip(0..4_u8)
+            .any(|(tank, player_number)| match tank {
+                Some(tank) => Some(player_number) != moved_player && overlaps(tank.x, tank.y),
+                None => false,
+            });
+
+        let covers_spawn = self
+            .level
+            .spawns
+            .iter()
+            .any(|spawn| overlaps(spawn.x, spawn.y));
+
+        !covers_block && !covers_tank && !covers_spawn
     }
 
     fn place_block(
@@ -212,36 +204,114 @@ impl Editor {
         block_type: BlockType,
         block_variant_getter: impl Fn(u16, u16) -> BlockVariant,
     ) {
-        for x in left_top_x..right_bottom_x + 1{
-            'label: for y in left_top_y..right_bottom_y + 1 {
-                for tank in self.level.tanks.iter().flatten() {
-                    if x >= tank.x && x <= tank.x + 3 && y >= tank.y && y <= tank.y + 3 {
-                        continue 'label;
-                    }
-                }
+        let mut changed = false;
 
-                let block_variant = block_variant_getter(x, y);
+        for x in left_top_x..right_bottom_x + 1 {
+            for y in left_top_y..right_bottom_y + 1 {
+                if self.is_covered_by_spawn(x, y) {
+                    continue;
+                }
 
-                self.level.blocks.insert(Block {
+                let block = Block {
                     x,
                     y,
                     block_type,
-                    block_variant,
-                });
+                    block_variant: block_variant_getter(x, y),
+                };
+
+                self.statistics.remove_cell(&self.level, x, y);
+                changed |= self.level.set_block(block) != Some(block);
+                self.statistics.add_cell(&self.level, x, y);
+            }
+        }
+
+        if changed {
+            self.mark_level_changed();
+        }
+    }
+
+    // Fills every tile of the tile grid touched by the selection, leaving out quadrants under tanks,
+    // spawns or past the edge of the level
+    fn place_tiles(
+        &mut self,
+        left_top_x: u16,
+        left_top_y: u16,
+        right_bottom_x: u16,
+        right_bottom_y: u16,
+        block_type: BlockType,
+    ) {
+        let mut changed = false;
+        let (left_tile, top_tile) = Tile::containing(left_top_x, left_top_y);
+        let (right_tile, bottom_tile) = Tile::containing(right_bottom_x, right_bottom_y);
+
+        for tile_x in left_tile..right_tile + 1 {
+            for tile_y in top_tile..bottom_tile + 1 {
+                let mut tile = self.level.tile(tile_x, tile_y);
+                let mut cells = vec![];
+
+                for (i, quadrant) in BlockVariant::ALL.into_iter().enumerate() {
+                    let (x, y) = tile.cell(quadrant);
+
+                    if self.level.contains(x, y) && !self.is_covered_by_spawn(x, y) {
+                        tile.quadrants[i] = Some((block_type, quadrant));
+                        cells.push((x, y));
+                    }
+                }
+
+                self.statistics.remove_cells(&self.level, &cells);
+                changed |= self.level.set_tile(&tile) != tile;
+                self.statistics.add_cells(&self.level, &cells);
             }
         }
+
+        if changed {
+            self.mark_level_changed();
+        }
+    }
+
+    // Blocks are never placed under tanks or extra spawns
+    fn is_covered_by_spawn(&self, x: u16, y: u16) -> bool {
+        self.level.tank_at(x, y).is_some() || self.level.spawn_at(x, y).is_some()
+    }
+
+    // Changes the type of matching blocks, keeping their variants
+    fn replace_blocks(
+        &mut self,
+        left_top_x: u16,
+        left_top_y: u16,
+        right_bottom_x: u16,
+        right_bottom_y: u16,
+        replaced_block_type: BlockType,
+        new_block_type: BlockType,
+    ) {
+        let mut changed = false;
+
+        for x in left_top_x..right_bottom_x + 1 {
+            for y in left_top_y..right_bottom_y + 1 {
+                let mut block = match self.level.block_at(x, y) {
+                    Some(block) if block.block_type == replaced_block_type => block,
+                    _ => continue,
+                };
+
+                self.statistics.remove_cell(&self.level, x, y);
+                block.block_type = new_block_type;
+                changed |= self.level.set_block(block) != Some(block);
+                self.statistics.add_cell(&self.level, x, y);
+            }
+        }
+
+        if changed {
+            self.mark_level_changed();
+        }
     }
 
     fn erase(
         &mut self,
-        stdout: &mut Stdout,
-        horizontal_margin: u16,
-        vertical_margin: u16,
         mouse_map_x: u16,
         mouse_map_y: u16,
         first_selection_corner_x: u16,
         first_selection_corner_y: u16,
-    ) -> Result<()> {
+    ) {
         let (left_top_x, right_bottom_x) = {
             if first_selection_corner_x < mouse_map_x {
                 (first_selection_corner_x, mouse_map_x)
@@ -258,113 +328,276 @@ impl Editor {
             }
         };
 
-        self.level.blocks.retain(|block| {
-            !(block.x >= left_top_x
-                && block.x <= right_bottom_x
-                && block.y >= left_top_y
-                && block.y <= right_bottom_y)
-        });
+        let mut changed = false;
+
+        for x in left_top_x..right_bottom_x + 1 {
+            for y in left_top_y..right_bottom_y + 1 {
+                if self.level.block_at(x, y).is_some() {
+                    self.statistics.remove_cell(&self.level, x, y);
+                    self.level.remove_block(x, y);
+                    self.statistics.add_cell(&self.level, x, y);
+                    changed = true;
+                }
+            }
+        }
 
-        for i in 0..self.level.tanks.len() {
-            if let Some(tank) = &self.level.tanks[i] {
-                if !(tank.x + 3 < left_top_x
-                    || tank.x > right_bottom_x
-                    || tank.y + 3 < left_top_y
-                    || tank.y > right_bottom_y)
+        for tank in &mut self.level.tanks {
+            if let Some(Tank { x, y, .. }) = *tank {
+                if !(x + 3 < left_top_x
+                    || x > right_bottom_x
+                    || y + 3 < left_top_y
+                    || y > right_bottom_y)
                 {
-                    let Tank { x, y, .. } = *tank;
-                    self.level.tanks[i] = None;
-                    self.level.draw(
-                        stdout,
-                        horizontal_margin,
-                        vertical_margin,
-                        x,
-                        y,
-                        TANK_SIZE,
-                        TANK_SIZE,
-                    )?;
+                    *tank = None;
+                    changed = true;
                 }
             }
         }
 
-        Ok(())
+        let spawns = self.level.spawns.len();
+        self.level.spawns.retain(|spawn: &Spawn| {
+            spawn.x + 3 < left_top_x
+                || spawn.x > right_bottom_x
+                || spawn.y + 3 < left_top_y
+                || spawn.y > right_bottom_y
+        });
+
+        if changed || self.level.spawns.len() != spawns {
+            self.mark_level_changed();
+        }
     }
 
     pub(super) fn handle_sidebar_mouse_actions(
         &mut self,
-        mouse_state: &MouseState,
         horizontal_margin: u16,
         vertical_margin: u16,
+        mouse_state: &MouseState,
     ) {
-        if !matches!(self.first_selection_corner, None) {
+        if self.first_selection_corner.is_some() {
             self.first_selection_corner = None;
         }
 
-        if matches!(mouse_state.left_button, ButtonState::GettingReleased) {
-            if mouse_state.is_hovered(
-                horizontal_margin + LEVEL_MAP_WIDTH + 3,
-                vertical_margin + 10,
-                8,
-                4,
+        if !matches!(mouse_state.left_button, ButtonState::GettingReleased) {
+            return;
+        }
+
+        let x = horizontal_margin + self.camera.view_columns();
+        let hovered = |column: u16, y: u16, width: u16, height: u16| {
+            mouse_state.is_hovered(x + column, y, width, height)
+        };
+        let section_y = |section| {
+            self.sidebar
+                .section_row(section)
+                .map(|row| vertical_margin + row)
+        };
+        let level_y = section_y(SidebarSection::Level);
+        let blocks_y = section_y(SidebarSection::Blocks);
+        let tanks_y = section_y(SidebarSection::Tanks);
+        let tools_y = section_y(SidebarSection::Tools);
+        let reachability_y = section_y(SidebarSection::Reachability);
+        let buttons_y = section_y(SidebarSection::Buttons);
+        let warnings_y = section_y(SidebarSection::Warnings);
+
+        if let Some(page) = self.sidebar.clicked_page(mouse_state, x, vertical_margin) {
+            self.sidebar.page = page;
+        }
+
+        if let Some(y) = level_y {
+            if hovered(1, y + TITLE_ROW, METADATA_WIDTH, 0) {
+                self.start_editing_metadata(MetadataField::Title);
+                return;
+            } else if hovered(1, y + AUTHOR_ROW, METADATA_WIDTH, 0) {
+                self.start_editing_metadata(MetadataField::Author);
+                return;
+            } else if hovered(
+                1,
+                y + DESCRIPTION_ROW,
+                METADATA_WIDTH,
+                DESCRIPTION_LINES - 1,
             ) {
+                self.start_editing_metadata(MetadataField::Description);
+                return;
+            }
+        }
+
+        self.submit_metadata();
+
+        if let Some(y) = level_y {
+            let size_arrows = [
+                (WIDTH_ARROWS.0, -1, 0),
+                (WIDTH_ARROWS.1, 1, 0),
+                (HEIGHT_ARROWS.0, 0, -1),
+                (HEIGHT_ARROWS.1, 0, 1),
+            ];
+
+            for (arrow_x, width_steps, height_steps) in size_arrows {
+                if hovered(arrow_x, y + MAP_SIZE_ROW, 0, 0) {
+                    self.resize_level(width_steps, height_steps);
+                }
+            }
+        }
+
+        if let Some(y) = warnings_y {
+            for i in 0..(self.warnings.len() as u16).min(WARNINGS_LINES) {
+                if hovered(1, y + 1 + i, METADATA_WIDTH, 0) {
+                    self.toggle_warning_highlight(i as usize);
+                }
+            }
+        }
+
+        if let Some(y) = buttons_y {
+            if hovered(SAVE_BUTTON_X, y, 10, 2) {
+                self.save_failed = self.save().is_err();
+            }
+        }
+
+        if let Some(y) = reachability_y {
+            if hovered(1, y, METADATA_WIDTH, 0) {
+                self.toggle_reachability();
+            }
+        }
+
+        if let Some(y) = blocks_y {
+            if hovered(3, y, 8, 4) {
                 self.tool = Tool::FullBlock(BlockType::Brick);
-            } else if mouse_state.is_hovered(
-                horizontal_margin + LEVEL_MAP_WIDTH + 14,
-                vertical_margin + 10,
-                8,
-                4,
-            ) {
+            } else if hovered(14, y, 8, 4) {
                 self.tool = Tool::FullBlock(BlockType::Concrete);
-            } else if mouse_state.is_hovered(
-                horizontal_margin + LEVEL_MAP_WIDTH + 3,
-                vertical_margin + 15,
-                8,
-                4,
-            ) {
+            } else if hovered(3, y + 5, 8, 4) {
                 self.tool = Tool::FullBlock(BlockType::Water);
-            } else if mouse_state.is_hovered(
-                horizontal_margin + LEVEL_MAP_WIDTH + 14,
-                vertical_margin + 15,
-                8,
-                4,
-            ) {
+            } else if hovered(14, y + 5, 8, 4) {
                 self.tool = Tool::FullBlock(BlockType::Leaves);
-            } else if mouse_state.is_hovered(
-                horizontal_margin + LEVEL_MAP_WIDTH + 3,
-                vertical_margin + 20,
-                8,
-                4,
-            ) {
-                self.tool = Tool::Tank(0, Direction::Up);
-            } else if mouse_state.is_hovered(
-                horizontal_margin + LEVEL_MAP_WIDTH + 14,
-                vertical_margin + 20,
-                8,
-                4,
-            ) {
-                self.tool = Tool::Tank(1, Direction::Up);
-            } else if mouse_state.is_hovered(
-                horizontal_margin + LEVEL_MAP_WIDTH + 3,
-                vertical_margin + 25,
-                8,
-                4,
-            ) {
-                self.tool = Tool::Tank(2, Direction::Up);
-            } else if mouse_state.is_hovered(
-                horizontal_margin + LEVEL_MAP_WIDTH + 13,
-                vertical_margin + 25,
-                8,
-                4,
-            ) {
-                self.tool = Tool::Tank(3, Direction::Up);
-            } else if mouse_state.is_hovered(
-                horizontal_margin + LEVEL_MAP_WIDTH + 9,
-                vertical_margin + 30,
-                8,
-                4,
-            ) {
+            }
+        }
+
+        if let Some(y) = tanks_y {
+            if hovered(3, y, 8, 3) {
+                self.select_tank_tool(0);
+            } else if hovered(14, y, 8, 3) {
+                self.select_tank_tool(1);
+            } else if hovered(3, y + 5, 8, 3) {
+                self.select_tank_tool(2);
+            } else if hovered(13, y + 5, 8, 3) {
+                self.select_tank_tool(3);
+            } else if hovered(4, y + SHARED_SPAWN_ROW, 15, 0) {
+                self.tool = Tool::Spawn(None, Direction::Up);
+            }
+        }
+
+        if let Some(y) = tools_y {
+            if hovered(3, y, 8, 4) {
                 self.tool = Tool::Eraser;
+            } else if hovered(14, y, 8, 4) {
+                self.tool = Tool::Picker;
+            } else if hovered(2, y + REPLACE_TOOL_ROW, 7, 1) {
+                if matches!(self.tool, Tool::Replace(_, _)) {
+                    self.replace_block_types.0 = self.replace_block_types.0.next();
+                }
+                self.select_replace_tool();
+            } else if hovered(15, y + REPLACE_TOOL_ROW, 7, 1) {
+                if matches!(self.tool, Tool::Replace(_, _)) {
+                    self.replace_block_types.1 = self.replace_block_types.1.next();
+                }
+                self.select_replace_tool();
+            } else if hovered(11, y + REPLACE_TOOL_ROW, 2, 1) {
+                let (replaced_block_type, new_block_type) = self.replace_block_types;
+                self.replace_blocks(
+                    0,
+                    0,
+                    self.level.width - 1,
+                    self.level.height - 1,
+                    replaced_block_type,
+                    new_block_type,
+                );
+                self.select_replace_tool();
             }
         }
     }
+
+    fn select_replace_tool(&mut self) {
+        let (replaced_block_type, new_block_type) = self.replace_block_types;
+        self.tool = Tool::Replace(replaced_block_type, new_block_type);
+    }
+
+    // Second click on the tank of a player switches to placing its extra spawns, and back
+    fn select_tank_tool(&mut self, player_number: u8) {
+        self.tool = match self.tool {
+            Tool::Tank(selected, direction) if selected == player_number => {
+                Tool::Spawn(Some(player_number), direction)
+            }
+            _ => Tool::Tank(player_number, Direction::Up),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::level::{statistics::LevelStatistics, Level};
+
+    #[test]
+    fn full_blocks_fill_tiles_around_tanks() {
+        let mut level = Level::new();
+        level.tanks[0] = Some(Tank {
+            x: 0,
+            y: 0,
+            direction: Direction::Up,
+        });
+        let mut editor = Editor::with_level(level);
+
+        // Selection from the middle of one tile to the middle of another covers both whole
+        editor.place_tiles(3, 3, 4, 4, BlockType::Water);
+
+        for (tile_x, tile_y) in [(1, 2), (2, 1), (2, 2)] {
+            let tile = editor.level.tile(tile_x, tile_y);
+            assert!(tile.block_type() == Some(BlockType::Water));
+        }
+
+        // Tile under the tank is left empty
+        assert!(editor.level.tile(1, 1).blocks().next().is_none());
+        assert_eq!(editor.statistics.block_count(BlockType::Water), 12);
+    }
+
+    #[test]
+    fn full_blocks_in_the_center_column_keep_statistics_right() {
+        let mut editor = Editor::with_level(Level::new());
+        let center_x = editor.level.width / 2 - 1;
+
+        // Both halves of the mirrored pair are in the same tile
+        editor.place_tiles(center_x, 0, center_x + 1, 1, BlockType::Brick);
+        assert_eq!(editor.statistics.symmetry_score(), 100);
+
+        editor.place_block(center_x, 2, center_x + 1, 2, BlockType::Brick, |_, _| {
+            BlockVariant::LeftTop
+        });
+        editor.place_tiles(center_x, 2, center_x + 1, 3, BlockType::Water);
+
+        let recounted = LevelStatistics::new(&editor.level);
+        assert_eq!(
+            editor.statistics.symmetry_score(),
+            recounted.symmetry_score()
+        );
+        assert_eq!(editor.statistics.total_blocks(), 8);
+    }
+
+    #[test]
+    fn edits_without_effect_leave_the_level_unchanged() {
+        let mut editor = Editor::with_level(Level::new());
+
+        editor.erase(0, 0, 5, 5);
+        editor.replace_blocks(0, 0, 5, 5, BlockType::Brick, BlockType::Water);
+        assert!(!editor.unsaved_changes);
+
+        editor.place_tiles(0, 0, 1, 1, BlockType::Brick);
+        assert!(editor.unsaved_changes);
+
+        // Same blocks again, and a replacement with nothing to replace
+        editor.unsaved_changes = false;
+        editor.place_tiles(0, 0, 1, 1, BlockType::Brick);
+        editor.place_block(0, 0, 0, 0, BlockType::Brick, |_, _| BlockVariant::LeftTop);
+        editor.replace_blocks(0, 0, 5, 5, BlockType::Concrete, BlockType::Water);
+        assert!(!editor.unsaved_changes);
+
+        editor.erase(0, 0, 0, 0);
+        assert!(editor.unsaved_changes);
+    }
 }
@@ -1,99 +1,923 @@
 mod draw_sidebar;
 mod handle_mouse_actions;
+mod reachability;
+mod recovery;
 mod tool;
 
+use self::draw_sidebar::MetadataField;
+use self::reachability::Reachability;
+use self::recovery::{RecoverySnapshot, AUTOSAVE_INTERVAL};
 use self::tool::Tool;
-use super::Mode;
+use super::{menu::Menu, Mode, SIDEBAR_WIDTH};
+use crate::game::dialog::{Dialog, DIALOG_WIDTH};
 use crate::game::level::block::BlockType;
-use crate::game::level::{Level, LEVEL_MAP_WIDTH, LEVEL_SIZE};
-use crate::game::{input::InputState, options::Options};
+use crate::game::level::camera::{Camera, MapView};
+use crate::game::level::generator::{generate, random_seed, GeneratorParameters};
+use crate::game::level::statistics::LevelStatistics;
+use crate::game::level::templates::{Template, TEMPLATES};
+use crate::game::level::validation::{validate, Warning};
+use crate::game::level::{
+    Level, MAP_VIEW_SIZE, MAX_AUTHOR_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_LEVEL_SIZE,
+    MAX_TITLE_LENGTH, MIN_LEVEL_SIZE,
+};
+use crate::game::render_target::RenderTarget;
+use crate::game::text_field::{TextField, TextFieldEvent};
+use crate::game::theme::Theme;
+use crate::game::{
+    input::{ButtonState, InputState, MouseState, WindowState},
+    options::Options,
+};
 use crossterm::Result;
-use draw_sidebar::draw_sidebar;
-use std::io::Stdout;
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    queue,
+    style::{Color, Print, SetBackgroundColor, SetForegroundColor},
+};
+use draw_sidebar::{
+    draw_block_tools, draw_level_metadata, draw_map_size, draw_other_tools,
+    draw_reachability_panel, draw_replace_tool, draw_save_button, draw_save_status, draw_sidebar,
+    draw_statistics, draw_tank_tools, draw_warnings, SidebarLayout, SidebarSection,
+    PAGED_SIDEBAR_HEIGHT, SIDEBAR_HEIGHT,
+};
+use std::io;
+use std::mem;
+use std::path::PathBuf;
 use std::time::Duration;
 
 pub struct Editor {
     tool: Tool,
     level: Level,
     first_selection_corner: Option<(u16, u16)>,
+    edited_metadata_field: Option<(MetadataField, TextField)>,
+    replace_block_types: (BlockType, BlockType), // Remembered between uses of the replace tool
+    validation_pending: bool, // Level is validated at most once per frame, after the changes
+    warnings: Vec<Warning>,
+    highlighted_warning: Option<usize>,
+    file_path: Option<PathBuf>,
+    template_dialog: Option<Dialog>,
+    generator_dialog: Option<Dialog>,
+    recovery: Option<(Dialog, RecoverySnapshot)>, // Session offered for restoring
+    time_since_autosave: Duration,
+    camera: Camera,
+    sidebar: SidebarLayout,
+    time_since_pan: Duration, // Time the mouse has been resting at the edge of the map
+    autosave_pending: bool,
+    unsaved_changes: bool,
+    save_failed: bool, // Until the level is changed again
+    exit_dialog: Option<(Dialog, ExitAction)>,
+    quit: bool,
+    reachability: Option<Reachability>, // Reachable area of a player, shown on the map
+    statistics: LevelStatistics,
+}
+
+fn template_dialog() -> Dialog {
+    let mut buttons = TEMPLATES.map(Template::name).to_vec();
+    // Last button, after all templates
+    buttons.push("Generate");
+
+    Dialog::new("Choose a template for the new level", &buttons)
+}
+
+fn generator_dialog() -> Dialog {
+    Dialog::new(
+        "Generate a random arena for how many players?",
+        &["2 players", "3 players", "4 players", "Back"],
+    )
+}
+
+// Maps are resized by whole tiles
+const RESIZE_STEP: u16 = 2;
+// Cells scrolled per arrow key press
+const PAN_STEP: i16 = 2;
+// Time between steps of scrolling while the mouse rests at the edge of the map
+const EDGE_PAN_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Copy, Clone)]
+enum ExitAction {
+    LeaveEditor,
+    QuitApplication,
 }
 
 impl Editor {
     pub fn new() -> Self {
+        let mut editor = Editor::with_level(Level::new());
+
+        editor.recovery = RecoverySnapshot::load().map(|snapshot| {
+            (
+                Dialog::new(
+                    "Restore the unsaved level from the last session?",
+                    &["Restore", "Discard"],
+                ),
+                snapshot,
+            )
+        });
+
+        // New level is started only if there is no unsaved one to restore
+        if editor.recovery.is_none() {
+            editor.template_dialog = Some(template_dialog());
+        }
+
+        editor
+    }
+
+    // Opens the given level without asking for a template or restoring the last session
+    pub fn with_level(level: Level) -> Self {
         Editor {
             tool: Tool::FullBlock(BlockType::Brick),
-            level: Level::new(),
+            statistics: LevelStatistics::new(&level),
+            level,
             first_selection_corner: None,
+            edited_metadata_field: None,
+            replace_block_types: (BlockType::Brick, BlockType::Concrete),
+            validation_pending: true,
+            warnings: vec![],
+            highlighted_warning: None,
+            file_path: None,
+            template_dialog: None,
+            generator_dialog: None,
+            recovery: None,
+            time_since_autosave: Duration::ZERO,
+            camera: Camera::new(MAP_VIEW_SIZE, MAP_VIEW_SIZE),
+            sidebar: SidebarLayout::new(),
+            time_since_pan: Duration::ZERO,
+            autosave_pending: false,
+            unsaved_changes: false,
+            save_failed: false,
+            exit_dialog: None,
+            quit: false,
+            reachability: None,
         }
     }
-}
 
-impl Mode for Editor {
-    fn draw(
+    // Largest view of the map that fits in the window next to the sidebar
+    fn fitted_camera(&self, window_state: &WindowState, options: &Options) -> Camera {
+        let mut camera = self.camera;
+        camera.renderer = options.map_renderer(window_state);
+        camera.fit(
+            window_state.width.saturating_sub(SIDEBAR_WIDTH),
+            window_state.height,
+            MAP_VIEW_SIZE,
+            &self.level,
+        );
+        camera
+    }
+
+    // Sidebar is split into pages when the window is too short for all of it
+    fn sidebar_layout(&self, window_state: &WindowState) -> SidebarLayout {
+        SidebarLayout {
+            paged: window_state.height < SIDEBAR_HEIGHT,
+            ..self.sidebar
+        }
+    }
+
+    fn has_dialog(&self) -> bool {
+        self.recovery.is_some()
+            || self.template_dialog.is_some()
+            || self.generator_dialog.is_some()
+            || self.exit_dialog.is_some()
+    }
+
+    // Draws the open dialog over the map and handles its buttons
+    fn handle_dialogs(
         &mut self,
-        stdout: &mut Stdout,
-        _delta_time: Duration,
+        target: &mut impl RenderTarget,
         horizontal_margin: u16,
         vertical_margin: u16,
-        refresh: bool,
-        input_state: &InputState,
-        _options: &Options,
+        mouse_state: &MouseState,
     ) -> Result<Option<Box<dyn Mode>>> {
-        let InputState { mouse_state, .. } = input_state;
+        if self.recovery.is_some() {
+            self.handle_recovery_dialog(target, horizontal_margin, vertical_margin, mouse_state)?;
+        } else if self.template_dialog.is_some() {
+            self.handle_template_dialog(target, horizontal_margin, vertical_margin, mouse_state)?;
+        } else if self.generator_dialog.is_some() {
+            self.handle_generator_dialog(target, horizontal_margin, vertical_margin, mouse_state)?;
+        } else if self.exit_dialog.is_some() {
+            return self.handle_exit_dialog(
+                target,
+                horizontal_margin,
+                vertical_margin,
+                mouse_state,
+            );
+        }
 
-        if refresh {
-            draw_sidebar(stdout, horizontal_margin + LEVEL_MAP_WIDTH, vertical_margin)?;
-            self.level.draw(
-                stdout,
+        Ok(None)
+    }
+
+    fn draw_dialog(
+        &self,
+        target: &mut impl RenderTarget,
+        dialog: &Dialog,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        mouse_state: &MouseState,
+    ) -> Result<Option<usize>> {
+        dialog.draw(
+            target,
+            horizontal_margin,
+            vertical_margin,
+            self.camera.view_columns(),
+            self.camera.view_rows(),
+            mouse_state,
+        )
+    }
+
+    fn handle_recovery_dialog(
+        &mut self,
+        target: &mut impl RenderTarget,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        mouse_state: &MouseState,
+    ) -> Result<()> {
+        let clicked_button = match &self.recovery {
+            Some((dialog, _)) => self.draw_dialog(
+                target,
+                dialog,
                 horizontal_margin,
                 vertical_margin,
+                mouse_state,
+            )?,
+            None => return Ok(()),
+        };
+
+        match clicked_button {
+            // Restore
+            Some(0) => {
+                if let Some((_, snapshot)) = self.recovery.take() {
+                    self.level = snapshot.level;
+                    self.camera.reset();
+                    self.statistics = LevelStatistics::new(&self.level);
+                    self.tool = snapshot.tool;
+                    self.file_path = snapshot.file_path;
+                    self.mark_level_changed();
+                }
+            }
+            // Discard
+            Some(_) => {
+                self.recovery = None;
+                self.template_dialog = Some(template_dialog());
+                RecoverySnapshot::remove();
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    fn handle_template_dialog(
+        &mut self,
+        target: &mut impl RenderTarget,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        mouse_state: &MouseState,
+    ) -> Result<()> {
+        let clicked_button = match &self.template_dialog {
+            Some(dialog) => self.draw_dialog(
+                target,
+                dialog,
+                horizontal_margin,
+                vertical_margin,
+                mouse_state,
+            )?,
+            None => return Ok(()),
+        };
+
+        match clicked_button {
+            Some(i) if i == TEMPLATES.len() => {
+                self.template_dialog = None;
+                self.generator_dialog = Some(generator_dialog());
+            }
+            Some(i) => {
+                self.template_dialog = None;
+                self.start_level(TEMPLATES[i].build());
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    fn handle_generator_dialog(
+        &mut self,
+        target: &mut impl RenderTarget,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        mouse_state: &MouseState,
+    ) -> Result<()> {
+        let clicked_button = match &self.generator_dialog {
+            Some(dialog) => self.draw_dialog(
+                target,
+                dialog,
+                horizontal_margin,
+                vertical_margin,
+                mouse_state,
+            )?,
+            None => return Ok(()),
+        };
+
+        match clicked_button {
+            // Back
+            Some(3) => {
+                self.generator_dialog = None;
+                self.template_dialog = Some(template_dialog());
+            }
+            Some(i) => {
+                let parameters = GeneratorParameters::new(random_seed(), i as u8 + 2);
+                self.generator_dialog = None;
+                self.start_level(generate(&parameters));
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    fn start_level(&mut self, level: Level) {
+        self.level = level;
+        self.camera.reset();
+        self.statistics = LevelStatistics::new(&self.level);
+        // Untouched new level is not worth saving, so the level is not marked as changed
+        self.validation_pending = true;
+    }
+
+    fn autosave(&mut self, delta_time: Duration) {
+        self.time_since_autosave += delta_time;
+
+        if self.autosave_pending && self.time_since_autosave >= AUTOSAVE_INTERVAL {
+            let snapshot = RecoverySnapshot {
+                level: self.level.clone(),
+                tool: self.tool,
+                file_path: self.file_path.clone(),
+            };
+
+            // Failing to autosave must not interrupt editing, it will be retried later
+            if snapshot.save().is_ok() {
+                self.autosave_pending = false;
+            }
+            self.time_since_autosave = Duration::ZERO;
+        }
+    }
+
+    // Clicking the highlighted warning again removes the highlight. Warnings without cells, like
+    // the one about no tanks at all, have nothing to highlight.
+    fn toggle_warning_highlight(&mut self, warning_index: usize) {
+        match self.warnings.get(warning_index) {
+            Some(warning) if !warning.cells.is_empty() => {}
+            _ => return,
+        }
+
+        self.highlighted_warning = if self.highlighted_warning == Some(warning_index) {
+            None
+        } else {
+            Some(warning_index)
+        };
+    }
+
+    fn draw_warning_highlight(
+        &self,
+        target: &mut impl RenderTarget,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+    ) -> Result<()> {
+        let warning = match self.highlighted_warning.and_then(|i| self.warnings.get(i)) {
+            Some(warning) => warning,
+            None => return Ok(()),
+        };
+
+        queue!(
+            target,
+            SetBackgroundColor(Color::DarkRed),
+            SetForegroundColor(Color::Red)
+        )?;
+
+        let visible_cells = warning
+            .cells
+            .iter()
+            .filter_map(|(x, y)| self.camera.view_position(*x, *y));
+
+        for (view_x, view_y) in visible_cells {
+            queue!(
+                target,
+                cursor::MoveTo(horizontal_margin + view_x, vertical_margin + view_y),
+                Print(self.camera.shade())
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn start_editing_metadata(&mut self, field: MetadataField) {
+        self.submit_metadata();
+
+        let text_field = match field {
+            MetadataField::Title => TextField::new(&self.level.title, MAX_TITLE_LENGTH),
+            MetadataField::Author => TextField::new(&self.level.author, MAX_AUTHOR_LENGTH),
+            MetadataField::Description => {
+                TextField::new(&self.level.description, MAX_DESCRIPTION_LENGTH)
+            }
+        };
+
+        self.edited_metadata_field = Some((field, text_field));
+    }
+
+    // Scrolls the map with arrow keys, or when the mouse rests at the edge of the map
+    fn pan(
+        &mut self,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        delta_time: Duration,
+        mouse_state: &MouseState,
+        key_events: &[KeyEvent],
+    ) {
+        let (mut columns, mut rows) = (0, 0);
+
+        // Arrow keys move the cursor while a metadata field is edited
+        if self.edited_metadata_field.is_none() {
+            for key_event in key_events {
+                match key_event.code {
+                    KeyCode::Left => columns -= PAN_STEP,
+                    KeyCode::Right => columns += PAN_STEP,
+                    KeyCode::Up => rows -= PAN_STEP,
+                    KeyCode::Down => rows += PAN_STEP,
+                    _ => {}
+                }
+            }
+        }
+
+        // Edges are one cell wide
+        let cell_columns = self.camera.cell_columns();
+        let (view_columns, view_rows) = (self.camera.view_columns(), self.camera.view_rows());
+
+        let edge_columns = if mouse_state.is_hovered(
+            horizontal_margin,
+            vertical_margin,
+            cell_columns - 1,
+            view_rows - 1,
+        ) {
+            -1
+        } else if mouse_state.is_hovered(
+            horizontal_margin + view_columns - cell_columns,
+            vertical_margin,
+            cell_columns - 1,
+            view_rows - 1,
+        ) {
+            1
+        } else {
+            0
+        };
+
+        let edge_rows =
+            if mouse_state.is_hovered(horizontal_margin, vertical_margin, view_columns - 1, 0) {
+                -1
+            } else if mouse_state.is_hovered(
+                horizontal_margin,
+                vertical_margin + view_rows - 1,
+                view_columns - 1,
                 0,
-                0,
-                LEVEL_SIZE,
-                LEVEL_SIZE,
+            ) {
+                1
+            } else {
+                0
+            };
+
+        if (edge_columns, edge_rows) == (0, 0) {
+            self.time_since_pan = Duration::ZERO;
+        } else {
+            self.time_since_pan += delta_time;
+
+            if self.time_since_pan >= EDGE_PAN_INTERVAL {
+                self.time_since_pan = Duration::ZERO;
+                columns += edge_columns;
+                rows += edge_rows;
+            }
+        }
+
+        self.camera.scroll(columns, rows, &self.level);
+    }
+
+    // Grows or shrinks the map by the given number of resize steps in each direction
+    fn resize_level(&mut self, width_steps: i16, height_steps: i16) {
+        let resized = |size: u16, steps: i16| {
+            (size as i16 + steps * RESIZE_STEP as i16)
+                .clamp(MIN_LEVEL_SIZE as i16, MAX_LEVEL_SIZE as i16) as u16
+        };
+        let width = resized(self.level.width, width_steps);
+        let height = resized(self.level.height, height_steps);
+
+        if (width, height) == (self.level.width, self.level.height) {
+            return;
+        }
+
+        self.level.resize(width, height);
+        self.camera.scroll(0, 0, &self.level);
+        self.statistics = LevelStatistics::new(&self.level);
+        self.mark_level_changed();
+    }
+
+    // Shows the next player's reachable area, or turns the overlay off after the last player
+    fn toggle_reachability(&mut self) {
+        let first_player = self
+            .reachability
+            .as_ref()
+            .map_or(0, |reachability| reachability.player_number + 1);
+        self.reachability =
+            (first_player..4).find_map(|player| Reachability::new(&self.level, player));
+    }
+
+    // Warnings and the reachability follow the changes made in the frame
+    fn validate(&mut self) {
+        if !mem::take(&mut self.validation_pending) {
+            return;
+        }
+
+        // Highlighted cells may not be valid anymore
+        self.highlighted_warning = None;
+        self.warnings = validate(&self.level);
+
+        // Tank of the shown player may have been erased, which turns the overlay off
+        if let Some(reachability) = &self.reachability {
+            self.reachability = Reachability::new(&self.level, reachability.player_number);
+        }
+    }
+
+    fn draw_screen(
+        &self,
+        target: &mut impl RenderTarget,
+        theme: &Theme,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+    ) -> Result<()> {
+        self.draw_sidebar(
+            target,
+            theme,
+            horizontal_margin + self.camera.view_columns(),
+            vertical_margin,
+        )?;
+
+        let mut view = MapView {
+            target,
+            camera: &self.camera,
+            theme,
+            horizontal_margin,
+            vertical_margin,
+        };
+        self.level.draw(&mut view)?;
+
+        if let Some(reachability) = &self.reachability {
+            reachability.draw_overlay(&mut view)?;
+        }
+
+        self.draw_warning_highlight(target, horizontal_margin, vertical_margin)
+    }
+
+    fn draw_sidebar(
+        &self,
+        target: &mut impl RenderTarget,
+        theme: &Theme,
+        x: u16,
+        y: u16,
+    ) -> Result<()> {
+        let height = self.camera.view_rows().max(self.sidebar.height());
+        draw_sidebar(target, x, y, height, &self.sidebar)?;
+        draw_save_status(target, x, y, self.unsaved_changes, self.save_failed)?;
+
+        let section_y = |section| self.sidebar.section_row(section).map(|row| y + row);
+
+        if let Some(y) = section_y(SidebarSection::Level) {
+            draw_level_metadata(
+                target,
+                x,
+                y,
+                &self.level,
+                self.edited_metadata_field.as_ref(),
             )?;
+            draw_statistics(target, theme, x, y, &self.level, &self.statistics)?;
+            draw_map_size(target, x, y, &self.level)?;
+        }
+
+        if let Some(y) = section_y(SidebarSection::Blocks) {
+            draw_block_tools(target, theme, x, y)?;
+        }
+
+        if let Some(y) = section_y(SidebarSection::Tanks) {
+            draw_tank_tools(target, theme, x, y)?;
+        }
+
+        if let Some(y) = section_y(SidebarSection::Tools) {
+            draw_other_tools(target, x, y)?;
+            let (replaced_block_type, new_block_type) = self.replace_block_types;
+            draw_replace_tool(target, theme, x, y, replaced_block_type, new_block_type)?;
+        }
+
+        if let Some(y) = section_y(SidebarSection::Reachability) {
+            draw_reachability_panel(target, theme, x, y, self.reachability.as_ref())?;
+        }
+
+        if let Some(y) = section_y(SidebarSection::Buttons) {
+            draw_save_button(target, x, y)?;
+        }
+
+        if let Some(y) = section_y(SidebarSection::Warnings) {
+            draw_warnings(target, x, y, &self.warnings, self.highlighted_warning)?;
+        }
+
+        Ok(())
+    }
+
+    // Returns the next mode, if the editor is left
+    fn handle_input(
+        &mut self,
+        delta_time: Duration,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        input_state: &InputState,
+    ) -> Option<Box<dyn Mode>> {
+        let InputState {
+            mouse_state,
+            key_events,
+            ..
+        } = input_state;
+
+        self.autosave(delta_time);
+
+        // Keys pressed after a field is submitted or cancelled are handled like any others
+        let mut key_events = key_events.as_slice();
+        if let Some((_, text_field)) = &mut self.edited_metadata_field {
+            let (event, remaining) = text_field.handle_key_events(key_events);
+            key_events = remaining;
+
+            match event {
+                TextFieldEvent::Editing => {}
+                TextFieldEvent::Submitted => self.submit_metadata(),
+                TextFieldEvent::Cancelled => self.edited_metadata_field = None,
+            }
+        }
+
+        self.pan(
+            horizontal_margin,
+            vertical_margin,
+            delta_time,
+            mouse_state,
+            key_events,
+        );
+
+        if mouse_state.is_clicked(
+            horizontal_margin + self.camera.view_columns() + 1,
+            vertical_margin,
+            2,
+            0,
+        ) {
+            self.submit_metadata();
+            return self.exit(ExitAction::LeaveEditor);
         }
 
         // Mouse is over the map
         if mouse_state.is_hovered(
             horizontal_margin,
             vertical_margin,
-            LEVEL_MAP_WIDTH,
-            LEVEL_SIZE,
+            self.camera.view_columns() - 1,
+            self.camera.view_rows() - 1,
         ) {
-            let mouse_level_x = mouse_state.column - (mouse_state.column - horizontal_margin) % 2;
-            let mouse_level_y = mouse_state.row;
-
-            let mouse_map_x = (mouse_level_x - horizontal_margin) / 2;
-            let mouse_map_y = mouse_level_y - vertical_margin;
+            let (mouse_map_x, mouse_map_y) = self.camera.map_position(
+                mouse_state.column - horizontal_margin,
+                mouse_state.row - vertical_margin,
+            );
 
             self.tool.handle_scroll(&mouse_state.scroll);
 
-            // self.tool.draw_tool(
-            //     stdout,
-            //     &self.first_selection_corner,
-            //     mouse_x,
-            //     mouse_y,
-            //     mouse_map_x,
-            //     mouse_map_y,
-            //     horizontal_margin,
-            //     vertical_margin,
-            // )?;
-
-            self.handle_map_mouse_actions(
-                stdout,
-                horizontal_margin,
-                vertical_margin,
-                mouse_state,
-                mouse_map_x,
-                mouse_map_y,
-            )?;
+            if matches!(mouse_state.left_button, ButtonState::GettingPressed) {
+                self.submit_metadata();
+            }
+
+            self.handle_map_mouse_actions(mouse_state, mouse_map_x, mouse_map_y);
         }
         // Mouse is over the sidebar
         else {
-            self.handle_sidebar_mouse_actions(mouse_state, horizontal_margin, vertical_margin);
+            self.handle_sidebar_mouse_actions(horizontal_margin, vertical_margin, mouse_state);
         }
 
-        Ok(None)
+        None
+    }
+
+    // Level has to be validated, autosaved and saved again before leaving
+    fn mark_level_changed(&mut self) {
+        self.validation_pending = true;
+        self.autosave_pending = true;
+        self.unsaved_changes = true;
+        self.save_failed = false;
+    }
+
+    fn submit_metadata(&mut self) {
+        if let Some((field, text_field)) = self.edited_metadata_field.take() {
+            let value = text_field.value.trim().to_string();
+
+            let edited_value = match field {
+                MetadataField::Title => &mut self.level.title,
+                MetadataField::Author => &mut self.level.author,
+                MetadataField::Description => &mut self.level.description,
+            };
+
+            // Level must always have a title
+            if *edited_value != value && !(field == MetadataField::Title && value.is_empty()) {
+                *edited_value = value;
+                self.mark_level_changed();
+            }
+        }
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        let path = match &self.file_path {
+            Some(path) => path.clone(),
+            None => self.level.default_path().ok_or(io::ErrorKind::NotFound)?,
+        };
+
+        self.level.save(&path)?;
+        self.file_path = Some(path);
+        self.unsaved_changes = false;
+        self.autosave_pending = false;
+        RecoverySnapshot::remove();
+
+        Ok(())
+    }
+
+    // Leaves the editor right away if there is nothing to lose, otherwise asks what to do first
+    fn exit(&mut self, exit_action: ExitAction) -> Option<Box<dyn Mode>> {
+        if self.unsaved_changes {
+            self.exit_dialog = Some((
+                Dialog::new(
+                    "Save changes to the level before leaving?",
+                    &["Save", "Discard", "Cancel"],
+                ),
+                exit_action,
+            ));
+            return None;
+        }
+
+        RecoverySnapshot::remove();
+
+        match exit_action {
+            ExitAction::LeaveEditor => Some(Box::new(Menu::new())),
+            ExitAction::QuitApplication => {
+                self.quit = true;
+                None
+            }
+        }
+    }
+
+    fn handle_exit_dialog(
+        &mut self,
+        target: &mut impl RenderTarget,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        mouse_state: &MouseState,
+    ) -> Result<Option<Box<dyn Mode>>> {
+        let (clicked_button, exit_action) = match &self.exit_dialog {
+            Some((dialog, exit_action)) => (
+                self.draw_dialog(
+                    target,
+                    dialog,
+                    horizontal_margin,
+                    vertical_margin,
+                    mouse_state,
+                )?,
+                *exit_action,
+            ),
+            None => return Ok(None),
+        };
+
+        match clicked_button {
+            // Save
+            Some(0) => {
+                if self.save().is_err() {
+                    self.exit_dialog = Some((
+                        Dialog::new(
+                            "Could not save the level. Leave anyway?",
+                            &["Try again", "Discard", "Cancel"],
+                        ),
+                        exit_action,
+                    ));
+                    return Ok(None);
+                }
+            }
+            // Discard
+            Some(1) => self.unsaved_changes = false,
+            // Cancel
+            Some(_) => {}
+            None => return Ok(None),
+        }
+
+        self.exit_dialog = None;
+
+        if self.unsaved_changes {
+            Ok(None)
+        } else {
+            Ok(self.exit(exit_action))
+        }
+    }
+}
+
+impl Mode for Editor {
+    fn draw(
+        &mut self,
+        mut target: &mut dyn RenderTarget,
+        delta_time: Duration,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        input_state: &InputState,
+        options: &Options,
+    ) -> Result<Option<Box<dyn Mode>>> {
+        let target = &mut target;
+        self.camera = self.fitted_camera(&input_state.window_state, options);
+        self.sidebar = self.sidebar_layout(&input_state.window_state);
+
+        // Dialogs take all the input while they are open
+        let dialog_shown = self.has_dialog();
+        let mut new_mode = None;
+
+        if !dialog_shown {
+            new_mode =
+                self.handle_input(delta_time, horizontal_margin, vertical_margin, input_state);
+        }
+
+        self.validate();
+        self.draw_screen(target, &options.theme, horizontal_margin, vertical_margin)?;
+
+        if dialog_shown {
+            new_mode = self.handle_dialogs(
+                target,
+                horizontal_margin,
+                vertical_margin,
+                &input_state.mouse_state,
+            )?;
+        }
+
+        Ok(new_mode)
+    }
+
+    fn screen_size(&self, window_state: &WindowState, options: &Options) -> (u16, u16) {
+        let camera = self.fitted_camera(window_state, options);
+        (
+            camera.view_columns() + SIDEBAR_WIDTH,
+            camera
+                .view_rows()
+                .max(self.sidebar_layout(window_state).height()),
+        )
+    }
+
+    // Map view has to fit the dialogs, and the sidebar has to fit one of its pages
+    fn min_window_size(&self, _window_state: &WindowState, _options: &Options) -> (u16, u16) {
+        (DIALOG_WIDTH + SIDEBAR_WIDTH, PAGED_SIDEBAR_HEIGHT)
+    }
+
+    fn request_quit(&mut self) -> bool {
+        self.submit_metadata();
+        self.exit(ExitAction::QuitApplication);
+        self.quit
+    }
+
+    fn wants_to_quit(&self) -> bool {
+        self.quit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{frame::Frame, level::tank::Direction, options::MapRendering};
+
+    #[test]
+    fn clicks_next_to_the_map_do_not_edit_it() {
+        let mut level = Level::new();
+        level.resize(60, 60);
+        let mut editor = Editor::with_level(level);
+        editor.tool = Tool::Tank(0, Direction::Up);
+
+        // Window fits 40 columns of cells, so the map goes on past the edge of the view
+        let mut mouse_state = MouseState::new();
+        mouse_state.column = 80;
+        mouse_state.row = 10;
+        mouse_state.left_button = ButtonState::GettingReleased;
+        let input_state = InputState {
+            mouse_state,
+            keyboard_state: vec![],
+            key_events: vec![],
+            window_state: WindowState {
+                width: 80 + SIDEBAR_WIDTH,
+                height: 50,
+            },
+        };
+
+        let mut options = Options::new();
+        options.map_rendering = MapRendering::Full;
+        options.ascii_only = false;
+
+        let mut frame = Frame::new();
+        frame.resize(80 + SIDEBAR_WIDTH, 50);
+        editor
+            .draw(&mut frame, Duration::ZERO, 0, 0, &input_state, &options)
+            .unwrap();
+
+        assert_eq!(editor.camera.width, 40);
+        assert!(editor.level.tanks[0].is_none());
     }
 }
@@ -0,0 +1,159 @@
+use crate::game::level::{
+    camera::MapView,
+    pathfinding::{block_grid, covered_cells, path_costs},
+    Level,
+};
+use crate::game::render_target::RenderTarget;
+use crossterm::{
+    cursor, queue,
+    style::{Color, Print, SetBackgroundColor, SetForegroundColor},
+    Result,
+};
+
+// Area which the tank of one player can get to and the path costs between the tanks, worked out
+// once after every change of the level instead of on every frame
+pub(super) struct Reachability {
+    pub player_number: u8,
+    cells: Vec<(u16, u16)>, // Empty cells covered by the tank on the way
+    pub spawn_path_costs: Vec<(u8, u8, Option<u32>)>,
+}
+
+impl Reachability {
+    // None if the player has no tank in the level
+    pub fn new(level: &Level, player_number: u8) -> Option<Self> {
+        let tank = level.tanks[player_number as usize].as_ref()?;
+        let grid = block_grid(level);
+        let costs = path_costs(&grid, tank);
+
+        let mut cells: Vec<(u16, u16)> = covered_cells(costs.keys())
+            .into_iter()
+            .filter(|(x, y)| {
+                grid[*y as usize][*x as usize].is_none()
+                    && level.tank_at(*x, *y).is_none()
+                    && level.spawn_at(*x, *y).is_none()
+            })
+            .collect();
+        cells.sort_unstable();
+
+        Some(Reachability {
+            player_number,
+            cells,
+            spawn_path_costs: spawn_path_costs(level),
+        })
+    }
+
+    // Tints the reachable cells
+    pub fn draw_overlay(&self, view: &mut MapView<impl RenderTarget>) -> Result<()> {
+        let color = view.theme.player_color(self.player_number);
+        queue!(
+            view.target,
+            SetBackgroundColor(Color::Black),
+            SetForegroundColor(color)
+        )?;
+
+        let overlay = view.camera.shade();
+
+        for (x, y) in &self.cells {
+            if let Some((screen_x, screen_y)) = view.screen_position(*x, *y) {
+                queue!(
+                    view.target,
+                    cursor::MoveTo(screen_x, screen_y),
+                    Print(&overlay)
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Cheapest path cost between each pair of placed tanks, None if they cannot reach each other
+fn spawn_path_costs(level: &Level) -> Vec<(u8, u8, Option<u32>)> {
+    let grid = block_grid(level);
+    let mut pair_costs = vec![];
+
+    for (tank, player_number) in level.tanks.iter().zip(0..4_u8) {
+        let tank = match tank {
+            Some(tank) => tank,
+            None => continue,
+        };
+        let costs = path_costs(&grid, tank);
+
+        for (other_tank, other_player_number) in level.tanks.iter().zip(0..4_u8) {
+            if let Some(other_tank) = other_tank
+                .as_ref()
+                .filter(|_| other_player_number > player_number)
+            {
+                pair_costs.push((
+                    player_number,
+                    other_player_number,
+                    costs.get(&(other_tank.x, other_tank.y)).copied(),
+                ));
+            }
+        }
+    }
+
+    pair_costs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::level::{
+        block::{Block, BlockType, BlockVariant},
+        pathfinding::BRICK_COST,
+        tank::{Direction, Tank},
+    };
+
+    fn tank(x: u16, y: u16) -> Option<Tank> {
+        Some(Tank {
+            x,
+            y,
+            direction: Direction::Up,
+        })
+    }
+
+    fn wall(level: &mut Level, x: u16, block_type: BlockType) {
+        for y in 0..level.height {
+            level.set_block(Block {
+                x,
+                y,
+                block_type,
+                block_variant: BlockVariant::LeftTop,
+            });
+        }
+    }
+
+    #[test]
+    fn spawn_path_costs_of_tank_pairs() {
+        let mut level = Level::new();
+        level.tanks = [tank(0, 0), tank(10, 0), None, tank(30, 0)];
+
+        // Bricks between the first two tanks, concrete in front of the last one
+        wall(&mut level, 5, BlockType::Brick);
+        wall(&mut level, 25, BlockType::Concrete);
+
+        assert_eq!(
+            spawn_path_costs(&level),
+            [
+                (0, 1, Some(10 + 4 * BRICK_COST)),
+                (0, 3, None),
+                (1, 3, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn reachable_cells_stop_at_impassable_blocks() {
+        let mut level = Level::new();
+        level.tanks[1] = tank(0, 0);
+        wall(&mut level, 5, BlockType::Water);
+
+        let reachability = Reachability::new(&level, 1).unwrap();
+        assert!(reachability.cells.contains(&(4, level.height - 1)));
+        assert!(!reachability.cells.contains(&(0, 0)));
+        assert!(reachability.cells.iter().all(|(x, _)| *x < 5));
+
+        assert!(Reachability::new(&level, 0).is_none());
+    }
+}
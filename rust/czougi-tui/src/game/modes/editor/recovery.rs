@@ -0,0 +1,51 @@
+use super::tool::Tool;
+use crate::game::{level::Level, paths::state_dir};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::PathBuf,
+    time::Duration,
+};
+
+pub(super) const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+const RECOVERY_FILE_NAME: &str = "editor_recovery.json";
+
+// Unsaved editor session written periodically, so that it can be restored after a crash
+#[derive(Serialize, Deserialize)]
+pub(super) struct RecoverySnapshot {
+    pub(super) level: Level,
+    pub(super) tool: Tool,
+    pub(super) file_path: Option<PathBuf>,
+}
+
+impl RecoverySnapshot {
+    pub(super) fn load() -> Option<Self> {
+        let file = File::open(recovery_file_path()?).ok()?;
+        serde_json::from_reader(BufReader::new(file)).ok()
+    }
+
+    pub(super) fn save(&self) -> io::Result<()> {
+        let path = recovery_file_path().ok_or(io::ErrorKind::NotFound)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Write to a temporary file first, so that a crash while saving does not lose the previous snapshot
+        let temporary_path = path.with_extension("json.tmp");
+        serde_json::to_writer(BufWriter::new(File::create(&temporary_path)?), self)?;
+        fs::rename(temporary_path, path)
+    }
+
+    pub(super) fn remove() {
+        if let Some(path) = recovery_file_path() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+fn recovery_file_path() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join(RECOVERY_FILE_NAME))
+}
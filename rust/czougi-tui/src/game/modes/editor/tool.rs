@@ -1,16 +1,24 @@
+use serde::{Deserialize, Serialize};
+
 use crate::game::{
     input::ScrollState,
     level::{
         block::{BlockType, BlockVariant},
         tank::Direction,
+        tile::Tile,
+        Level,
     },
 };
 
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub(super) enum Tool {
     SmallBlock(BlockType, BlockVariant),
     FullBlock(BlockType),
-    Tank(u8, Direction), // Player number, direction of tank
+    Tank(u8, Direction),          // Player number, direction of tank
+    Spawn(Option<u8>, Direction), // Extra spawn of the player, or shared if None
     Eraser,
+    Picker,
+    Replace(BlockType, BlockType), // Replaced block type, new block type
 }
 
 impl Tool {
@@ -40,7 +48,7 @@ impl Tool {
                 ScrollState::Down => *self = Tool::SmallBlock(*block_type, BlockVariant::LeftTop),
                 _ => {}
             },
-            Tool::Tank(_, direction) => {
+            Tool::Tank(_, direction) | Tool::Spawn(_, direction) => {
                 *direction = match scroll {
                     ScrollState::Up => match direction {
                         Direction::Up => Direction::Left,
@@ -57,13 +65,34 @@ impl Tool {
                     _ => unreachable!(),
                 }
             }
-            Tool::Eraser => {}
+            Tool::Eraser | Tool::Picker | Tool::Replace(_, _) => {}
         }
     }
 
+    // Returns the tool that would recreate whatever is under the given map position
+    pub(super) fn pick(level: &Level, x: u16, y: u16) -> Option<Tool> {
+        if let Some((player_number, tank)) = level.tank_at(x, y) {
+            return Some(Tool::Tank(player_number, tank.direction));
+        }
+
+        if let Some(i) = level.spawn_at(x, y) {
+            let spawn = &level.spawns[i];
+            return Some(Tool::Spawn(spawn.player_number, spawn.direction));
+        }
+
+        let (tile_x, tile_y) = Tile::containing(x, y);
+        if let Some(block_type) = level.tile(tile_x, tile_y).block_type() {
+            return Some(Tool::FullBlock(block_type));
+        }
+
+        level
+            .block_at(x, y)
+            .map(|block| Tool::SmallBlock(block.block_type, block.block_variant))
+    }
+
     // pub(super) fn draw_tool(
     //     &self,
-    //     stdout: &mut Stdout,
+    //     target: &mut impl RenderTarget,
     //     first_selection_corner: &Option<(u16, u16)>,
     //     mouse_x: u16,
     //     mouse_y: u16,
@@ -74,14 +103,14 @@ impl Tool {
     // ) -> Result<()> {
     //     if let Tool::Tank(player_number, direction) = &self {
     //         if mouse_map_x <= 46 && mouse_map_y <= 46 {
-    //             queue!(stdout, SetBackgroundColor(Color::Black))?;
-    //             draw_tank(stdout, mouse_x, mouse_y, *player_number, direction)?;
+    //             queue!(target, SetBackgroundColor(Color::Black))?;
+    //             draw_tank(target, mouse_x, mouse_y, *player_number, direction)?;
     //         }
     //         return Ok(());
     //     }
 
     //     if mouse_map_x <= 48 && mouse_map_y <= 48 {
-    //         type DrawFunctionPointer = fn(&mut Stdout, u16, u16) -> Result<()>;
+    //         type DrawFunctionPointer = fn(&mut impl RenderTarget, u16, u16) -> Result<()>;
     //         let (foreground, background, draw): (Color, Color, DrawFunctionPointer) = match self {
     //             Tool::Brick => (
     //                 BRICK_FOREGROUND_COLOR,
@@ -132,14 +161,14 @@ impl Tool {
     //         };
 
     //         queue!(
-    //             stdout,
+    //             target,
     //             SetBackgroundColor(background),
     //             SetForegroundColor(foreground)
     //         )?;
 
     //         for x in position_x_iterator {
     //             for y in position_y_iterator.clone() {
-    //                 draw(stdout, x, y)?;
+    //                 draw(target, x, y)?;
     //             }
     //         }
     //     }
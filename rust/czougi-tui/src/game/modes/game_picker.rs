@@ -1,26 +1,16 @@
 use super::editor::Editor;
+use super::generator_screen::GeneratorScreen;
 use super::menu::Menu;
 use super::Mode;
-use crate::game::drawing_utils::draw_background;
+use crate::game::drawing_utils::{draw_back_arrow, draw_background};
 use crate::game::input::InputState;
 use crate::game::options::Options;
+use crate::game::render_target::RenderTarget;
+use crate::game::theme::Theme;
 use crossterm::style::{Color, SetBackgroundColor, SetForegroundColor};
 use crossterm::{cursor, queue, style::Print, Result};
-use std::io::Stdout;
 use std::time::Duration;
 
-const OFFLINE_GAME_FRAME_COLOR: Color = Color::Rgb {
-    r: 24,
-    g: 204,
-    b: 36,
-};
-
-const ONLINE_GAME_FRAME_COLOR: Color = Color::Rgb {
-    r: 240,
-    g: 204,
-    b: 28,
-};
-
 enum Section {
     OfflineGame,
     OnlineGame,
@@ -33,35 +23,32 @@ pub struct GamePicker {
 impl Mode for GamePicker {
     fn draw(
         &mut self,
-        stdout: &mut Stdout,
+        mut target: &mut dyn RenderTarget,
         _delta_time: Duration,
         horizontal_margin: u16,
         vertical_margin: u16,
-        refresh: bool,
         input_state: &InputState,
-        _options: &Options,
+        options: &Options,
     ) -> Result<Option<Box<dyn Mode>>> {
+        let target = &mut target;
         let InputState { mouse_state, .. } = input_state;
 
-        if refresh {
-            self.refresh(stdout, horizontal_margin, vertical_margin)?;
-        }
-
         match self.current_section {
             Section::OfflineGame => {
                 if mouse_state.is_clicked(horizontal_margin + 22, vertical_margin + 2, 15, 3) {
                     self.current_section = Section::OnlineGame;
-                    self.refresh(stdout, horizontal_margin, vertical_margin)?;
                 } else if mouse_state.is_clicked(horizontal_margin + 107, vertical_margin + 4, 9, 5)
                 {
                     return Ok(Some(Box::new(Editor::new())));
+                } else if mouse_state.is_clicked(horizontal_margin + 10, vertical_margin + 6, 20, 2)
+                {
+                    return Ok(Some(Box::new(GeneratorScreen::new())));
                 }
             }
 
             Section::OnlineGame => {
                 if mouse_state.is_clicked(horizontal_margin + 7, vertical_margin + 2, 15, 3) {
                     self.current_section = Section::OfflineGame;
-                    self.refresh(stdout, horizontal_margin, vertical_margin)?;
                 }
             }
         }
@@ -70,6 +57,10 @@ impl Mode for GamePicker {
             return Ok(Some(Box::new(Menu::new())));
         }
 
+        draw_background(target, horizontal_margin, vertical_margin)?;
+        self.draw_frame(target, &options.theme, horizontal_margin, vertical_margin)?;
+        draw_back_arrow(target, horizontal_margin + 2, vertical_margin + 6)?;
+
         Ok(None)
     }
 }
@@ -81,32 +72,22 @@ impl GamePicker {
         }
     }
 
-    fn refresh(
-        &self,
-        stdout: &mut Stdout,
-        horizontal_margin: u16,
-        vertical_margin: u16,
-    ) -> Result<()> {
-        draw_background(stdout, horizontal_margin, vertical_margin)?;
-        self.draw_frame(stdout, horizontal_margin, vertical_margin)?;
-        self.draw_back_arrow(stdout, horizontal_margin + 2, vertical_margin + 6)?;
-
-        Ok(())
-    }
-
     fn draw_frame(
         &self,
-        stdout: &mut Stdout,
+        target: &mut impl RenderTarget,
+        theme: &Theme,
         horizontal_margin: u16,
         vertical_margin: u16,
     ) -> Result<()> {
-        match self.current_section {
-            Section::OfflineGame => queue!(stdout, SetForegroundColor(OFFLINE_GAME_FRAME_COLOR))?,
-            Section::OnlineGame => queue!(stdout, SetForegroundColor(ONLINE_GAME_FRAME_COLOR))?,
-        }
+        let frame_color = match self.current_section {
+            Section::OfflineGame => theme.ui.offline_game_frame,
+            Section::OnlineGame => theme.ui.online_game_frame,
+        };
+
+        queue!(target, SetForegroundColor(frame_color))?;
 
         queue!(
-            stdout,
+            target,
             SetBackgroundColor(Color::Black),
             cursor::MoveTo(horizontal_margin + 7, vertical_margin + 2),
             Print("┌──────────────┬─────────────┐"),
@@ -118,20 +99,20 @@ impl GamePicker {
 
         for x in [horizontal_margin + 7, horizontal_margin + 114] {
             for y in vertical_margin + 5..vertical_margin + 47 {
-                queue!(stdout, cursor::MoveTo(x, y), Print("│"),)?;
+                queue!(target, cursor::MoveTo(x, y), Print("│"),)?;
             }
         }
 
         for y in vertical_margin + 5..vertical_margin + 8 {
             queue!(
-                stdout,
+                target,
                 cursor::MoveTo(horizontal_margin + 106, y),
                 Print("│")
             )?;
         }
 
         queue!(
-            stdout,
+            target,
             cursor::MoveTo(horizontal_margin + 106, vertical_margin + 8),
             Print("└───────┤"),
             cursor::MoveTo(horizontal_margin + 107, vertical_margin + 5),
@@ -148,18 +129,26 @@ impl GamePicker {
 
         match self.current_section {
             Section::OfflineGame => queue!(
-                stdout,
+                target,
                 cursor::MoveTo(horizontal_margin + 24, vertical_margin + 3),
                 Print("Online game"),
-                SetBackgroundColor(OFFLINE_GAME_FRAME_COLOR),
+                SetBackgroundColor(frame_color),
                 cursor::MoveTo(horizontal_margin + 8, vertical_margin + 3),
                 Print(" Offline game "),
+                SetBackgroundColor(Color::Black),
+                SetForegroundColor(frame_color),
+                cursor::MoveTo(horizontal_margin + 10, vertical_margin + 6),
+                Print("┌───────────────────┐"),
+                cursor::MoveTo(horizontal_margin + 10, vertical_margin + 7),
+                Print("│ Edit random arena │"),
+                cursor::MoveTo(horizontal_margin + 10, vertical_margin + 8),
+                Print("└───────────────────┘"),
             )?,
             Section::OnlineGame => queue!(
-                stdout,
+                target,
                 cursor::MoveTo(horizontal_margin + 8, vertical_margin + 3),
                 Print(" Offline game "),
-                SetBackgroundColor(ONLINE_GAME_FRAME_COLOR),
+                SetBackgroundColor(frame_color),
                 cursor::MoveTo(horizontal_margin + 23, vertical_margin + 3),
                 Print(" Online game "),
             )?,
@@ -167,16 +156,4 @@ impl GamePicker {
 
         Ok(())
     }
-
-    fn draw_back_arrow(&self, stdout: &mut Stdout, x: u16, y: u16) -> Result<()> {
-        queue!(
-            stdout,
-            cursor::MoveTo(x, y),
-            SetBackgroundColor(Color::Black),
-            SetForegroundColor(Color::Red),
-            Print("◄--"),
-        )?;
-
-        Ok(())
-    }
 }
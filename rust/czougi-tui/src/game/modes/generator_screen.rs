@@ -0,0 +1,222 @@
+use super::editor::Editor;
+use super::game_picker::GamePicker;
+use super::Mode;
+use crate::game::drawing_utils::{clicked_choice, draw_back_arrow, draw_background, draw_choice};
+use crate::game::input::InputState;
+use crate::game::level::generator::{generate, random_seed, GeneratorParameters, Symmetry};
+use crate::game::options::Options;
+use crate::game::render_target::RenderTarget;
+use crossterm::style::{Color, SetBackgroundColor, SetForegroundColor};
+use crossterm::{cursor, queue, style::Print, Result};
+use std::time::Duration;
+
+const SETTINGS_X: u16 = 10;
+const PLAYERS_Y: u16 = 6;
+const SYMMETRY_Y: u16 = 11;
+// One row for every block type, four rows apart
+const DENSITIES_Y: u16 = 17;
+const SEED_Y: u16 = 35;
+// Past the longest seed
+const NEW_SEED_X: u16 = 27;
+const GENERATE_Y: u16 = 39;
+const PLAYER_CHOICES: [&str; 3] = ["2", "3", "4"];
+const DENSITY_LABELS: [&str; 4] = ["Brick", "Concrete", "Water", "Leaves"];
+// Percentages of the tiles, including the defaults of the generator
+const DENSITY_CHOICES: [u8; 8] = [0, 4, 6, 8, 12, 20, 30, 40];
+
+// Parameters of a random arena, which is opened in the editor once generated
+pub struct GeneratorScreen {
+    parameters: GeneratorParameters,
+}
+
+impl Mode for GeneratorScreen {
+    fn draw(
+        &mut self,
+        mut target: &mut dyn RenderTarget,
+        _delta_time: Duration,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        input_state: &InputState,
+        _options: &Options,
+    ) -> Result<Option<Box<dyn Mode>>> {
+        let target = &mut target;
+        let InputState { mouse_state, .. } = input_state;
+        let x = horizontal_margin + SETTINGS_X;
+        let symmetry_names = Symmetry::ALL.map(Symmetry::name);
+        let density_names = DENSITY_CHOICES.map(|density| format!("{}%", density));
+        let density_names: Vec<&str> = density_names.iter().map(String::as_str).collect();
+
+        if let Some(i) =
+            clicked_choice(mouse_state, x, vertical_margin + PLAYERS_Y, &PLAYER_CHOICES)
+        {
+            self.parameters.players = i as u8 + 2;
+        }
+
+        if let Some(i) = clicked_choice(
+            mouse_state,
+            x,
+            vertical_margin + SYMMETRY_Y,
+            &symmetry_names,
+        ) {
+            self.parameters.symmetry = Symmetry::ALL[i];
+        }
+
+        for (block, density) in self.parameters.densities.iter_mut().enumerate() {
+            let y = vertical_margin + DENSITIES_Y + block as u16 * 4;
+
+            if let Some(i) = clicked_choice(mouse_state, x, y, &density_names) {
+                *density = DENSITY_CHOICES[i];
+            }
+        }
+
+        if mouse_state.is_clicked(x + NEW_SEED_X, vertical_margin + SEED_Y, 9, 0) {
+            self.parameters.seed = random_seed();
+        }
+
+        if mouse_state.is_clicked(x, vertical_margin + GENERATE_Y, 13, 2) {
+            let level = generate(&self.parameters);
+            return Ok(Some(Box::new(Editor::with_level(level))));
+        }
+
+        if mouse_state.is_clicked(horizontal_margin, vertical_margin + 5, 6, 3) {
+            return Ok(Some(Box::new(GamePicker::new())));
+        }
+
+        draw_background(target, horizontal_margin, vertical_margin)?;
+        draw_back_arrow(target, horizontal_margin + 2, vertical_margin + 6)?;
+
+        draw_choice(
+            target,
+            x,
+            vertical_margin + PLAYERS_Y,
+            "Players",
+            &PLAYER_CHOICES,
+            Some(self.parameters.players as usize - 2),
+        )?;
+
+        // The generator only keeps positions equal for more players with quadrant symmetry
+        let symmetry = if self.parameters.players > 2 {
+            Symmetry::Quadrant
+        } else {
+            self.parameters.symmetry
+        };
+        draw_choice(
+            target,
+            x,
+            vertical_margin + SYMMETRY_Y,
+            "Symmetry",
+            &symmetry_names,
+            Symmetry::ALL.iter().position(|other| *other == symmetry),
+        )?;
+
+        if self.parameters.players > 2 {
+            queue!(
+                target,
+                SetBackgroundColor(Color::Black),
+                SetForegroundColor(Color::DarkGrey),
+                cursor::MoveTo(x, vertical_margin + SYMMETRY_Y + 3),
+                Print("More than two players always use quadrant symmetry"),
+            )?;
+        }
+
+        for (block, label) in DENSITY_LABELS.iter().enumerate() {
+            let density = self.parameters.densities[block];
+
+            draw_choice(
+                target,
+                x,
+                vertical_margin + DENSITIES_Y + block as u16 * 4,
+                label,
+                &density_names,
+                DENSITY_CHOICES.iter().position(|other| *other == density),
+            )?;
+        }
+
+        queue!(
+            target,
+            SetBackgroundColor(Color::Black),
+            SetForegroundColor(Color::White),
+            cursor::MoveTo(x, vertical_margin + SEED_Y),
+            Print(format!("Seed {}", self.parameters.seed)),
+            SetBackgroundColor(Color::DarkGrey),
+            cursor::MoveTo(x + NEW_SEED_X, vertical_margin + SEED_Y),
+            Print(" New seed "),
+            SetBackgroundColor(Color::Black),
+            cursor::MoveTo(x, vertical_margin + GENERATE_Y),
+            Print("┌────────────┐"),
+            cursor::MoveTo(x, vertical_margin + GENERATE_Y + 1),
+            Print("│  Generate  │"),
+            cursor::MoveTo(x, vertical_margin + GENERATE_Y + 2),
+            Print("└────────────┘"),
+        )?;
+
+        Ok(None)
+    }
+}
+
+impl GeneratorScreen {
+    pub fn new() -> Self {
+        GeneratorScreen::with_seed(random_seed())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        GeneratorScreen {
+            parameters: GeneratorParameters::new(seed, 2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::frame::Frame;
+    use crate::game::input::{ButtonState, MouseState, WindowState};
+    use crate::game::{MIN_HEIGHT, MIN_WIDTH};
+
+    fn click(generator_screen: &mut GeneratorScreen, column: u16, row: u16) -> bool {
+        let mut mouse_state = MouseState::new();
+        mouse_state.column = column;
+        mouse_state.row = row;
+        mouse_state.left_button = ButtonState::GettingReleased;
+        let input_state = InputState {
+            mouse_state,
+            keyboard_state: vec![],
+            key_events: vec![],
+            window_state: WindowState {
+                width: MIN_WIDTH,
+                height: MIN_HEIGHT,
+            },
+        };
+        let mut frame = Frame::new();
+        frame.resize(MIN_WIDTH, MIN_HEIGHT);
+
+        generator_screen
+            .draw(
+                &mut frame,
+                Duration::ZERO,
+                0,
+                0,
+                &input_state,
+                &Options::new(),
+            )
+            .unwrap()
+            .is_some()
+    }
+
+    #[test]
+    fn chosen_parameters_used() {
+        let mut generator_screen = GeneratorScreen::with_seed(7);
+
+        // Three players, quadrant symmetry and no concrete
+        assert!(!click(&mut generator_screen, 15, PLAYERS_Y + 2));
+        assert!(!click(&mut generator_screen, 50, SYMMETRY_Y + 2));
+        assert!(!click(&mut generator_screen, 11, DENSITIES_Y + 6));
+
+        let parameters = &generator_screen.parameters;
+        assert_eq!(parameters.players, 3);
+        assert!(parameters.symmetry == Symmetry::Quadrant);
+        assert_eq!(parameters.densities, [20, 0, 4, 8]);
+
+        assert!(click(&mut generator_screen, 12, GENERATE_Y + 1));
+    }
+}
@@ -1,12 +1,13 @@
-use super::editor::Editor;
 use super::game_picker::GamePicker;
+use super::options_screen::OptionsScreen;
 use super::Mode;
 use crate::game::drawing_utils::{draw_background, draw_multi_line_text};
 use crate::game::input::{InputState, MouseState};
 use crate::game::options::Options;
+use crate::game::render_target::RenderTarget;
+use crate::game::theme::Theme;
 use crossterm::style::{Color, SetBackgroundColor, SetForegroundColor};
 use crossterm::{cursor, queue, style::Print, Result};
-use std::io::Stdout;
 use std::time::Duration;
 
 // Text generated using this tool: https://patorjk.com/software/taag/#p=display&f=Big%20Money-ne&t=CZOUGI
@@ -55,25 +56,28 @@ pub struct Menu;
 impl Mode for Menu {
     fn draw(
         &mut self,
-        stdout: &mut Stdout,
+        mut target: &mut dyn RenderTarget,
         _delta_time: Duration,
         horizontal_margin: u16,
         vertical_margin: u16,
-        refresh: bool,
         input_state: &InputState,
-        _options: &Options,
+        options: &Options,
     ) -> Result<Option<Box<dyn Mode>>> {
+        let target = &mut target;
         let InputState { mouse_state, .. } = input_state;
 
-        if refresh {
-            draw_background(stdout, horizontal_margin, vertical_margin)?;
-            self.draw_title(stdout, horizontal_margin + 32, vertical_margin + 5)?;
-            self.draw_buttons_frames(stdout, horizontal_margin, vertical_margin)?;
-            self.draw_signature(stdout, horizontal_margin + 105, vertical_margin + 49)?;
-        }
+        draw_background(target, horizontal_margin, vertical_margin)?;
+        self.draw_title(
+            target,
+            &options.theme,
+            horizontal_margin + 32,
+            vertical_margin + 5,
+        )?;
+        self.draw_buttons_frames(target, horizontal_margin, vertical_margin)?;
+        self.draw_signature(target, horizontal_margin + 105, vertical_margin + 49)?;
 
         self.draw_play_button(
-            stdout,
+            target,
             mouse_state,
             horizontal_margin + 37,
             vertical_margin + 21,
@@ -89,7 +93,7 @@ impl Mode for Menu {
         }
 
         self.draw_options_button(
-            stdout,
+            target,
             mouse_state,
             horizontal_margin + 37,
             vertical_margin + 31,
@@ -101,7 +105,7 @@ impl Mode for Menu {
             BUTTON_WIDTH,
             BUTTON_HEIGHT,
         ) {
-            return Ok(Some(Box::new(Editor::new())));
+            return Ok(Some(Box::new(OptionsScreen::new())));
         }
 
         Ok(None)
@@ -113,16 +117,22 @@ impl Menu {
         Menu
     }
 
-    fn draw_title(&self, stdout: &mut Stdout, x: u16, y: u16) -> Result<()> {
-        queue!(stdout, SetForegroundColor(Color::Red))?;
-        draw_multi_line_text(stdout, TITLE.iter(), x, y)?;
+    fn draw_title(
+        &self,
+        target: &mut impl RenderTarget,
+        theme: &Theme,
+        x: u16,
+        y: u16,
+    ) -> Result<()> {
+        queue!(target, SetForegroundColor(theme.ui.title))?;
+        draw_multi_line_text(target, TITLE.iter(), x, y)?;
 
         Ok(())
     }
 
-    fn draw_signature(&self, stdout: &mut Stdout, x: u16, y: u16) -> Result<()> {
+    fn draw_signature(&self, target: &mut impl RenderTarget, x: u16, y: u16) -> Result<()> {
         queue!(
-            stdout,
+            target,
             SetForegroundColor(Color::White),
             SetBackgroundColor(Color::Black),
             cursor::MoveTo(x, y),
@@ -134,25 +144,25 @@ impl Menu {
 
     fn draw_buttons_frames(
         &self,
-        stdout: &mut Stdout,
+        target: &mut impl RenderTarget,
         horizontal_margin: u16,
         vertical_margin: u16,
     ) -> Result<()> {
         queue!(
-            stdout,
+            target,
             SetBackgroundColor(Color::Black),
             SetForegroundColor(Color::White)
         )?;
 
         draw_multi_line_text(
-            stdout,
+            target,
             BUTTON_FRAME.iter(),
             horizontal_margin + 35,
             vertical_margin + 20,
         )?;
 
         draw_multi_line_text(
-            stdout,
+            target,
             BUTTON_FRAME.iter(),
             horizontal_margin + 35,
             vertical_margin + 30,
@@ -163,7 +173,7 @@ impl Menu {
 
     fn draw_button_content(
         &self,
-        stdout: &mut Stdout,
+        target: &mut impl RenderTarget,
         button_text: [&str; 5],
         x: u16,
         y: u16,
@@ -171,47 +181,47 @@ impl Menu {
     ) -> Result<()> {
         if hovered {
             queue!(
-                stdout,
+                target,
                 SetBackgroundColor(Color::Black),
                 SetForegroundColor(Color::White)
             )?;
         } else {
             queue!(
-                stdout,
+                target,
                 SetBackgroundColor(Color::White),
                 SetForegroundColor(Color::Black)
             )?;
         }
 
-        draw_multi_line_text(stdout, button_text.iter(), x, y)?;
+        draw_multi_line_text(target, button_text.iter(), x, y)?;
 
         Ok(())
     }
 
     fn draw_play_button(
         &self,
-        stdout: &mut Stdout,
+        target: &mut impl RenderTarget,
         mouse_state: &MouseState,
         x: u16,
         y: u16,
     ) -> Result<()> {
         let is_play_button_hovered =
             mouse_state.is_hovered(x - 2, y - 1, BUTTON_WIDTH, BUTTON_HEIGHT);
-        self.draw_button_content(stdout, PLAY_BUTTON_TEXT, x, y, is_play_button_hovered)?;
+        self.draw_button_content(target, PLAY_BUTTON_TEXT, x, y, is_play_button_hovered)?;
 
         Ok(())
     }
 
     fn draw_options_button(
         &self,
-        stdout: &mut Stdout,
+        target: &mut impl RenderTarget,
         mouse_state: &MouseState,
         x: u16,
         y: u16,
     ) -> Result<()> {
         let is_options_button_hovered =
             mouse_state.is_hovered(x - 2, y - 1, BUTTON_WIDTH, BUTTON_HEIGHT);
-        self.draw_button_content(stdout, OPTIONS_BUTTON_TEXT, x, y, is_options_button_hovered)?;
+        self.draw_button_content(target, OPTIONS_BUTTON_TEXT, x, y, is_options_button_hovered)?;
 
         Ok(())
     }
@@ -1,23 +1,52 @@
-use super::input::InputState;
+use super::input::{InputState, WindowState};
 use super::options::Options;
+use super::render_target::RenderTarget;
+use super::{MIN_HEIGHT, MIN_WIDTH};
 use crossterm::Result;
-use std::io::Stdout;
 use std::time::Duration;
 
 pub mod editor;
 pub mod game_picker;
+pub mod generator_screen;
 pub mod menu;
+pub mod options_screen;
 
 pub const SIDEBAR_WIDTH: u16 = 25;
 pub trait Mode {
+    // Handles the input and draws the whole screen, which starts out blank on every frame.
+    // Target is a trait object to keep modes boxable. Drawing functions are generic over the
+    // target instead, so it is passed on to them as `&mut target`.
     fn draw(
         &mut self,
-        stdout: &mut Stdout,
+        target: &mut dyn RenderTarget,
         delta_time: Duration,
         horizontal_margin: u16,
         vertical_margin: u16,
-        refresh: bool,
         input_state: &InputState,
         options: &Options,
     ) -> Result<Option<Box<dyn Mode>>>;
+
+    // Called when the user wants to quit the application. A mode can return false to keep the
+    // application running, e.g. to ask about unsaved work, and report later with wants_to_quit.
+    fn request_quit(&mut self) -> bool {
+        true
+    }
+
+    fn wants_to_quit(&self) -> bool {
+        false
+    }
+
+    // Lets the mode change the options, like the options screen does
+    fn apply_options(&mut self, _options: &mut Options) {}
+
+    // Size of the screen drawn in the window, which is centered in it. Most modes have a fixed
+    // layout of this size, which is also their minimum window size.
+    fn screen_size(&self, _window_state: &WindowState, _options: &Options) -> (u16, u16) {
+        (MIN_WIDTH, MIN_HEIGHT)
+    }
+
+    // Smaller windows only get told how large they have to be
+    fn min_window_size(&self, _window_state: &WindowState, _options: &Options) -> (u16, u16) {
+        (MIN_WIDTH, MIN_HEIGHT)
+    }
 }
@@ -0,0 +1,296 @@
+use super::menu::Menu;
+use super::Mode;
+use crate::game::drawing_utils::{clicked_choice, draw_back_arrow, draw_background, draw_choice};
+use crate::game::input::InputState;
+use crate::game::level::block::{draw_full_block, BlockType};
+use crate::game::level::tank::{draw_tank, Direction};
+use crate::game::options::{ColorPalette, MapRendering, Options};
+use crate::game::render_target::RenderTarget;
+use crate::game::theme::Theme;
+use crossterm::style::{Color, SetBackgroundColor, SetForegroundColor};
+use crossterm::{cursor, queue, style::Print, Result};
+use std::{mem, time::Duration};
+
+const THEME_LIST_X: u16 = 10;
+const THEME_LIST_Y: u16 = 6;
+const THEME_BUTTON_WIDTH: u16 = 30;
+// Themes beyond the bottom of the screen are left out
+const MAX_LISTED_THEMES: usize = 20;
+const PREVIEW_X: u16 = 50;
+// Settings are listed below the preview, in the same column
+const MAP_RENDERING_Y: u16 = 20;
+const COLOR_PALETTE_Y: u16 = 26;
+const ASCII_ONLY_Y: u16 = 32;
+const ASCII_ONLY_CHOICES: [&str; 2] = ["Off", "On"];
+
+pub struct OptionsScreen {
+    themes: Vec<Theme>,
+    selected_theme: Option<usize>,
+    theme_changed: bool,
+    // Chosen in this frame, until they are applied
+    map_rendering: Option<MapRendering>,
+    color_palette: Option<ColorPalette>,
+    ascii_only: Option<bool>,
+}
+
+impl Mode for OptionsScreen {
+    fn draw(
+        &mut self,
+        mut target: &mut dyn RenderTarget,
+        _delta_time: Duration,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+        input_state: &InputState,
+        options: &Options,
+    ) -> Result<Option<Box<dyn Mode>>> {
+        let target = &mut target;
+        let InputState { mouse_state, .. } = input_state;
+
+        self.selected_theme = self
+            .themes
+            .iter()
+            .position(|theme| theme.name == options.theme.name);
+
+        for i in 0..self.themes.len().min(MAX_LISTED_THEMES) {
+            if mouse_state.is_clicked(
+                horizontal_margin + THEME_LIST_X,
+                vertical_margin + THEME_LIST_Y + i as u16 * 2,
+                THEME_BUTTON_WIDTH - 1,
+                0,
+            ) && self.selected_theme != Some(i)
+            {
+                self.selected_theme = Some(i);
+                self.theme_changed = true;
+            }
+        }
+
+        let settings_x = horizontal_margin + PREVIEW_X;
+        let map_rendering_names = MapRendering::ALL.map(MapRendering::name);
+
+        if let Some(i) = clicked_choice(
+            mouse_state,
+            settings_x,
+            vertical_margin + MAP_RENDERING_Y,
+            &map_rendering_names,
+        ) {
+            self.map_rendering = Some(MapRendering::ALL[i]);
+        }
+
+        let color_palette_names = ColorPalette::ALL.map(ColorPalette::name);
+
+        if let Some(i) = clicked_choice(
+            mouse_state,
+            settings_x,
+            vertical_margin + COLOR_PALETTE_Y,
+            &color_palette_names,
+        ) {
+            self.color_palette = Some(ColorPalette::ALL[i]);
+        }
+
+        if let Some(i) = clicked_choice(
+            mouse_state,
+            settings_x,
+            vertical_margin + ASCII_ONLY_Y,
+            &ASCII_ONLY_CHOICES,
+        ) {
+            self.ascii_only = Some(i == 1);
+        }
+
+        if mouse_state.is_clicked(horizontal_margin, vertical_margin + 5, 6, 3) {
+            return Ok(Some(Box::new(Menu::new())));
+        }
+
+        draw_background(target, horizontal_margin, vertical_margin)?;
+        draw_back_arrow(target, horizontal_margin + 2, vertical_margin + 6)?;
+        self.draw_theme_list(target, horizontal_margin, vertical_margin)?;
+        self.draw_preview(
+            target,
+            &options.theme,
+            horizontal_margin + PREVIEW_X,
+            vertical_margin + THEME_LIST_Y,
+        )?;
+
+        let map_rendering = self.map_rendering.unwrap_or(options.map_rendering);
+        draw_choice(
+            target,
+            settings_x,
+            vertical_margin + MAP_RENDERING_Y,
+            "Map renderer",
+            &map_rendering_names,
+            MapRendering::ALL
+                .iter()
+                .position(|other| *other == map_rendering),
+        )?;
+
+        // Image can still be chosen, e.g. for a terminal that is not detected right
+        if map_rendering == MapRendering::Image && options.graphics_protocol.is_none() {
+            queue!(
+                target,
+                SetBackgroundColor(Color::Black),
+                SetForegroundColor(Color::DarkGrey),
+                cursor::MoveTo(settings_x, vertical_margin + MAP_RENDERING_Y + 3),
+                Print("No graphics support detected, drawn as Full"),
+            )?;
+        }
+
+        let color_palette = self.color_palette.unwrap_or(options.color_palette);
+        draw_choice(
+            target,
+            settings_x,
+            vertical_margin + COLOR_PALETTE_Y,
+            "Colors",
+            &color_palette_names,
+            ColorPalette::ALL
+                .iter()
+                .position(|other| *other == color_palette),
+        )?;
+
+        // Starts out as detected from the terminal
+        let ascii_only = self.ascii_only.unwrap_or(options.ascii_only);
+        draw_choice(
+            target,
+            settings_x,
+            vertical_margin + ASCII_ONLY_Y,
+            "ASCII only",
+            &ASCII_ONLY_CHOICES,
+            Some(ascii_only as usize),
+        )?;
+
+        Ok(None)
+    }
+
+    fn apply_options(&mut self, options: &mut Options) {
+        if let Some(map_rendering) = self.map_rendering.take() {
+            options.map_rendering = map_rendering;
+        }
+
+        if let Some(color_palette) = self.color_palette.take() {
+            options.color_palette = color_palette;
+        }
+
+        if let Some(ascii_only) = self.ascii_only.take() {
+            options.choose_ascii_only(ascii_only);
+        }
+
+        if !mem::take(&mut self.theme_changed) {
+            return;
+        }
+
+        if let Some(i) = self.selected_theme {
+            options.theme = self.themes[i].clone();
+        }
+    }
+}
+
+impl OptionsScreen {
+    pub fn new() -> Self {
+        OptionsScreen::with_themes(Theme::all())
+    }
+
+    pub fn with_themes(themes: Vec<Theme>) -> Self {
+        OptionsScreen {
+            themes,
+            selected_theme: None,
+            theme_changed: false,
+            map_rendering: None,
+            color_palette: None,
+            ascii_only: None,
+        }
+    }
+
+    fn draw_theme_list(
+        &self,
+        target: &mut impl RenderTarget,
+        horizontal_margin: u16,
+        vertical_margin: u16,
+    ) -> Result<()> {
+        queue!(
+            target,
+            SetBackgroundColor(Color::Black),
+            SetForegroundColor(Color::White),
+            cursor::MoveTo(
+                horizontal_margin + THEME_LIST_X,
+                vertical_margin + THEME_LIST_Y - 2
+            ),
+            Print("Theme"),
+        )?;
+
+        for (i, theme) in self.themes.iter().take(MAX_LISTED_THEMES).enumerate() {
+            let (background, foreground) = if self.selected_theme == Some(i) {
+                (Color::White, Color::Black)
+            } else {
+                (Color::DarkGrey, Color::White)
+            };
+            let name: String = theme
+                .name
+                .chars()
+                .take(THEME_BUTTON_WIDTH as usize - 2)
+                .collect();
+
+            queue!(
+                target,
+                SetBackgroundColor(background),
+                SetForegroundColor(foreground),
+                cursor::MoveTo(
+                    horizontal_margin + THEME_LIST_X,
+                    vertical_margin + THEME_LIST_Y + i as u16 * 2
+                ),
+                Print(format!(
+                    " {:<width$} ",
+                    name,
+                    width = THEME_BUTTON_WIDTH as usize - 2
+                )),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Blocks of every type and tanks of every player, as they look on the map
+    fn draw_preview(
+        &self,
+        target: &mut impl RenderTarget,
+        theme: &Theme,
+        x: u16,
+        y: u16,
+    ) -> Result<()> {
+        queue!(
+            target,
+            SetBackgroundColor(Color::Black),
+            SetForegroundColor(theme.ui.title),
+            cursor::MoveTo(x, y - 2),
+            Print("Preview"),
+        )?;
+
+        let block_types = [
+            BlockType::Brick,
+            BlockType::Concrete,
+            BlockType::Water,
+            BlockType::Leaves,
+        ];
+
+        for (i, block_type) in block_types.into_iter().enumerate() {
+            let block_x = x + i as u16 * 10;
+
+            draw_full_block(target, theme, block_type, block_x, y)?;
+            draw_full_block(target, theme, block_type, block_x + 4, y)?;
+            draw_full_block(target, theme, block_type, block_x, y + 2)?;
+            draw_full_block(target, theme, block_type, block_x + 4, y + 2)?;
+        }
+
+        queue!(target, SetBackgroundColor(Color::Black))?;
+
+        for player_number in 0..4 {
+            draw_tank(
+                target,
+                theme,
+                x + player_number as u16 * 10,
+                y + 6,
+                player_number,
+                Direction::Up,
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,23 @@
+use super::{
+    colors::ColorSupport,
+    glyphs::detect_ascii_only,
+    input::WindowState,
+    level::{camera::MapRenderer, image::GraphicsProtocol},
+    paths::data_dir,
+    theme::Theme,
+    MIN_HEIGHT, MIN_WIDTH,
+};
 use device_query::Keycode;
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const SAVED_OPTIONS_FILE_NAME: &str = "options.json";
+
 pub struct PlayerKeybindings {
     pub up: Keycode,
     pub down: Keycode,
@@ -8,15 +26,99 @@ pub struct PlayerKeybindings {
     pub shoot: Keycode,
 }
 
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MapRendering {
+    Automatic, // Compact only when the window is too small for the full one
+    Full,
+    Compact,
+    Braille,
+    Image, // Sixel or Kitty graphics, if the terminal supports any of them
+}
+
+impl MapRendering {
+    pub const ALL: [MapRendering; 5] = [
+        MapRendering::Automatic,
+        MapRendering::Full,
+        MapRendering::Compact,
+        MapRendering::Braille,
+        MapRendering::Image,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MapRendering::Automatic => "Automatic",
+            MapRendering::Full => "Full",
+            MapRendering::Compact => "Compact",
+            MapRendering::Braille => "Braille",
+            MapRendering::Image => "Image",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ColorPalette {
+    Automatic, // Guessed from the environment
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorPalette {
+    pub const ALL: [ColorPalette; 4] = [
+        ColorPalette::Automatic,
+        ColorPalette::TrueColor,
+        ColorPalette::Ansi256,
+        ColorPalette::Ansi16,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorPalette::Automatic => "Automatic",
+            ColorPalette::TrueColor => "Truecolor",
+            ColorPalette::Ansi256 => "256",
+            ColorPalette::Ansi16 => "16",
+        }
+    }
+}
+
 pub struct Options {
+    #[allow(dead_code)]
     pub keybindings: [PlayerKeybindings; 4],
     pub interval: Duration,
+    pub map_rendering: MapRendering,
+    pub graphics_protocol: Option<GraphicsProtocol>,
+    // Colors are converted to the nearest ones of smaller palettes when sent to the terminal
+    pub color_palette: ColorPalette,
+    detected_color_support: ColorSupport,
+    // Other glyphs are replaced with ASCII ones and the map is drawn with the ASCII renderer.
+    // Detected from the terminal, until it is changed in the options screen.
+    pub ascii_only: bool,
+    ascii_only_chosen: bool,
+    pub theme: Theme,
+}
+
+// Choices made in the options screen, kept in the data directory next to the themes.
+// Missing ones keep their defaults, so that `ascii_only` stays detected until it is chosen.
+#[derive(Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedOptions {
+    theme: Option<String>,
+    map_rendering: Option<MapRendering>,
+    color_palette: Option<ColorPalette>,
+    ascii_only: Option<bool>,
 }
 
 impl Options {
     pub fn new() -> Self {
         Options {
             interval: Duration::from_millis(1000 / 60),
+            map_rendering: MapRendering::Automatic,
+            graphics_protocol: GraphicsProtocol::detect(),
+            color_palette: ColorPalette::Automatic,
+            detected_color_support: ColorSupport::detect(),
+            ascii_only: detect_ascii_only(),
+            ascii_only_chosen: false,
+            theme: Theme::classic(),
             keybindings: [
                 PlayerKeybindings {
                     up: Keycode::W,
@@ -49,4 +151,181 @@ impl Options {
             ],
         }
     }
+
+    // Options chosen in an earlier run, if they were saved
+    pub fn load() -> Self {
+        let mut options = Options::new();
+
+        if let Some(saved) = saved_options_path().and_then(|path| SavedOptions::load(&path)) {
+            options.apply_saved(saved, &Theme::all());
+        }
+
+        options
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = saved_options_path().ok_or(io::ErrorKind::NotFound)?;
+        self.saved().save(&path)
+    }
+
+    pub fn choose_ascii_only(&mut self, ascii_only: bool) {
+        self.ascii_only = ascii_only;
+        self.ascii_only_chosen = true;
+    }
+
+    pub fn saved(&self) -> SavedOptions {
+        SavedOptions {
+            theme: Some(self.theme.name.clone()),
+            map_rendering: Some(self.map_rendering),
+            color_palette: Some(self.color_palette),
+            ascii_only: self.ascii_only_chosen.then_some(self.ascii_only),
+        }
+    }
+
+    // Themes that are no longer in the data directory are left at the default one
+    fn apply_saved(&mut self, saved: SavedOptions, themes: &[Theme]) {
+        if let Some(theme) = saved
+            .theme
+            .and_then(|name| themes.iter().find(|theme| theme.name == name))
+        {
+            self.theme = theme.clone();
+        }
+
+        if let Some(map_rendering) = saved.map_rendering {
+            self.map_rendering = map_rendering;
+        }
+
+        if let Some(color_palette) = saved.color_palette {
+            self.color_palette = color_palette;
+        }
+
+        if let Some(ascii_only) = saved.ascii_only {
+            self.choose_ascii_only(ascii_only);
+        }
+    }
+
+    pub fn color_support(&self) -> ColorSupport {
+        match self.color_palette {
+            ColorPalette::Automatic => self.detected_color_support,
+            ColorPalette::TrueColor => ColorSupport::TrueColor,
+            ColorPalette::Ansi256 => ColorSupport::Ansi256,
+            ColorPalette::Ansi16 => ColorSupport::Ansi16,
+        }
+    }
+
+    pub fn map_renderer(&self, window_state: &WindowState) -> MapRenderer {
+        // Half blocks have no ASCII replacement, so the window has to fit the full size map
+        if self.ascii_only {
+            return MapRenderer::Ascii;
+        }
+
+        match self.map_rendering {
+            MapRendering::Automatic
+                if window_state.width >= MIN_WIDTH && window_state.height >= MIN_HEIGHT =>
+            {
+                MapRenderer::Full
+            }
+            MapRendering::Automatic | MapRendering::Compact => MapRenderer::Compact,
+            MapRendering::Full => MapRenderer::Full,
+            MapRendering::Braille => MapRenderer::Braille,
+            MapRendering::Image => self
+                .graphics_protocol
+                .map_or(MapRenderer::Full, MapRenderer::Image),
+        }
+    }
+}
+
+impl SavedOptions {
+    fn load(path: &Path) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        serde_json::from_reader(BufReader::new(file)).ok()
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()
+    }
+}
+
+fn saved_options_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(SAVED_OPTIONS_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn image_falls_back_to_full_without_graphics_protocol() {
+        let window_state = WindowState {
+            width: MIN_WIDTH,
+            height: MIN_HEIGHT,
+        };
+        let mut options = Options::new();
+        options.ascii_only = false;
+        options.map_rendering = MapRendering::Image;
+
+        options.graphics_protocol = None;
+        assert!(options.map_renderer(&window_state) == MapRenderer::Full);
+
+        options.graphics_protocol = Some(GraphicsProtocol::Kitty);
+        assert!(options.map_renderer(&window_state) == MapRenderer::Image(GraphicsProtocol::Kitty));
+    }
+
+    #[test]
+    fn chosen_color_palette_overrides_the_detected_one() {
+        let mut options = Options::new();
+        options.detected_color_support = ColorSupport::Ansi256;
+        assert_eq!(options.color_support(), ColorSupport::Ansi256);
+
+        options.color_palette = ColorPalette::Ansi16;
+        assert_eq!(options.color_support(), ColorSupport::Ansi16);
+
+        options.color_palette = ColorPalette::TrueColor;
+        assert_eq!(options.color_support(), ColorSupport::TrueColor);
+    }
+
+    #[test]
+    fn saved_options_restored() {
+        let dir = env::temp_dir().join(format!("czougi-options-{}", std::process::id()));
+        let path = dir.join(SAVED_OPTIONS_FILE_NAME);
+        let themes = [Theme::classic(), Theme::high_contrast()];
+
+        let mut options = Options::new();
+        options.theme = Theme::high_contrast();
+        options.map_rendering = MapRendering::Braille;
+        options.color_palette = ColorPalette::Ansi256;
+        options.choose_ascii_only(true);
+        options.saved().save(&path).unwrap();
+
+        let mut restored = Options::new();
+        restored.ascii_only = false;
+        restored.apply_saved(SavedOptions::load(&path).unwrap(), &themes);
+        assert_eq!(restored.theme.name, "High contrast");
+        assert!(restored.map_rendering == MapRendering::Braille);
+        assert!(restored.color_palette == ColorPalette::Ansi256);
+        assert!(restored.ascii_only);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unchosen_ascii_only_stays_detected() {
+        let mut options = Options::new();
+        options.ascii_only = true;
+        assert!(options.saved().ascii_only.is_none());
+
+        let mut restored = Options::new();
+        restored.ascii_only = false;
+        let saved: SavedOptions = serde_json::from_str(r#"{"theme": "Removed theme"}"#).unwrap();
+        restored.apply_saved(saved, &[Theme::classic()]);
+        assert!(!restored.ascii_only);
+        assert_eq!(restored.theme.name, Theme::classic().name);
+    }
 }
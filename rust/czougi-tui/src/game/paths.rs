@@ -0,0 +1,27 @@
+use std::{env, path::PathBuf};
+
+// Directory for files created by users, like levels made in the editor
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir).join("czougi"));
+    }
+
+    if cfg!(windows) {
+        env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("czougi"))
+    } else {
+        env::var_os("HOME").map(|dir| PathBuf::from(dir).join(".local/share/czougi"))
+    }
+}
+
+// Directory for files that have to outlive the process but are not meant to be edited by users
+pub fn state_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_STATE_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir).join("czougi"));
+    }
+
+    if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("czougi"))
+    } else {
+        env::var_os("HOME").map(|dir| PathBuf::from(dir).join(".local/state/czougi"))
+    }
+}
@@ -0,0 +1,91 @@
+use std::io::{self, Write};
+
+use super::{colors::ColorSupport, frame::Frame};
+use crossterm::Result;
+
+// Anything the game can be drawn on. Crossterm commands are queued on it like on the terminal.
+pub trait RenderTarget: Write {
+    fn resize(&mut self, width: u16, height: u16);
+
+    // Called once everything in the frame has been drawn
+    fn present(&mut self) -> Result<()>;
+}
+
+// Keeps the cells in memory only, e.g. for tests
+impl RenderTarget for Frame {
+    fn resize(&mut self, width: u16, height: u16) {
+        Frame::resize(self, width, height)
+    }
+
+    fn present(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// Sends the changes of every frame to the output, which can be the terminal, a file with an ANSI
+// dump or a connection to a remote client
+pub struct Terminal<W: Write> {
+    frame: Frame,
+    pub output: W,
+    color_support: ColorSupport,
+    ascii_only: bool,
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn new(output: W) -> Self {
+        Terminal {
+            frame: Frame::new(),
+            output,
+            color_support: ColorSupport::TrueColor,
+            ascii_only: false,
+        }
+    }
+
+    // Cells shown in the previous colors are sent again
+    pub fn set_color_support(&mut self, color_support: ColorSupport) {
+        if color_support != self.color_support {
+            self.color_support = color_support;
+            self.frame.invalidate();
+        }
+    }
+
+    pub fn set_ascii_only(&mut self, ascii_only: bool) {
+        if ascii_only != self.ascii_only {
+            self.ascii_only = ascii_only;
+            self.frame.invalidate();
+        }
+    }
+}
+
+impl<W: Write> Write for Terminal<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.frame.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.frame.flush()
+    }
+}
+
+impl<W: Write> RenderTarget for Terminal<W> {
+    fn resize(&mut self, width: u16, height: u16) {
+        self.frame.resize(width, height)
+    }
+
+    fn present(&mut self) -> Result<()> {
+        self.frame
+            .render(&mut self.output, self.color_support, self.ascii_only)
+    }
+}
+
+// Lets a target given as a trait object be passed on to functions generic over the target, as
+// queueing commands needs a sized writer
+impl<T: RenderTarget + ?Sized> RenderTarget for &mut T {
+    fn resize(&mut self, width: u16, height: u16) {
+        (**self).resize(width, height)
+    }
+
+    fn present(&mut self) -> Result<()> {
+        (**self).present()
+    }
+}
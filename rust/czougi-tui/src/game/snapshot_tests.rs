@@ -0,0 +1,284 @@
+// Screens drawn on an in-memory frame, compared with the snapshots in the `snapshots` directory.
+// Run the tests with UPDATE_SNAPSHOTS=1 to write the snapshots again after an intended change.
+
+use super::{
+    frame::Frame,
+    input::{ButtonState, InputState, MouseState, WindowState},
+    level::{
+        block::BlockType,
+        camera::{Camera, MapRenderer, MapView},
+        tank::{Direction, Spawn, Tank},
+        tile::Tile,
+        Level,
+    },
+    modes::{
+        editor::Editor, game_picker::GamePicker, generator_screen::GeneratorScreen, menu::Menu,
+        options_screen::OptionsScreen, Mode,
+    },
+    options::{ColorPalette, MapRendering, Options},
+    theme::Theme,
+    MIN_HEIGHT, MIN_WIDTH,
+};
+use crossterm::{
+    queue,
+    terminal::{Clear, ClearType},
+};
+use std::{env, fs, path::PathBuf, time::Duration};
+
+fn assert_snapshot(name: &str, frame: &Frame) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(format!("{}.txt", name));
+    let snapshot = frame.snapshot();
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, snapshot).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing snapshot {}, run with UPDATE_SNAPSHOTS=1", name));
+
+    assert!(
+        expected == snapshot,
+        "Screen differs from snapshot {}, run with UPDATE_SNAPSHOTS=1 if the change is intended:\n{}",
+        name,
+        snapshot
+    );
+}
+
+// Mouse resting in the corner of the window, away from anything that reacts to hovering
+fn idle_mouse() -> MouseState {
+    let mut mouse_state = MouseState::new();
+    mouse_state.column = MIN_WIDTH - 1;
+    mouse_state.row = MIN_HEIGHT - 1;
+    mouse_state
+}
+
+fn click(column: u16, row: u16) -> MouseState {
+    let mut mouse_state = MouseState::new();
+    mouse_state.column = column;
+    mouse_state.row = row;
+    mouse_state.left_button = ButtonState::GettingReleased;
+    mouse_state
+}
+
+fn draw(mode: &mut dyn Mode, frame: &mut Frame, mouse_state: MouseState) {
+    draw_in_window(mode, frame, MIN_WIDTH, MIN_HEIGHT, mouse_state);
+}
+
+// Blank screen and one tick of the mode, like in the game loop
+fn draw_in_window(
+    mode: &mut dyn Mode,
+    frame: &mut Frame,
+    width: u16,
+    height: u16,
+    mouse_state: MouseState,
+) {
+    let input_state = InputState {
+        mouse_state,
+        keyboard_state: vec![],
+        key_events: vec![],
+        window_state: WindowState { width, height },
+    };
+
+    queue!(frame, Clear(ClearType::All)).unwrap();
+    mode.draw(frame, Duration::ZERO, 0, 0, &input_state, &Options::new())
+        .unwrap();
+}
+
+fn window_frame() -> Frame {
+    let mut frame = Frame::new();
+    frame.resize(MIN_WIDTH, MIN_HEIGHT);
+    frame
+}
+
+// Tile of every block type in the top row, tanks and spawns below
+fn sample_level() -> Level {
+    let mut level = Level::new();
+    let block_types = [
+        BlockType::Brick,
+        BlockType::Concrete,
+        BlockType::Water,
+        BlockType::Leaves,
+    ];
+
+    for (i, block_type) in block_types.into_iter().enumerate() {
+        level.set_tile(&Tile::full(i as u16, 0, block_type));
+    }
+
+    level.tanks[0] = Some(Tank {
+        x: 0,
+        y: 3,
+        direction: Direction::Up,
+    });
+    level.tanks[1] = Some(Tank {
+        x: 5,
+        y: 3,
+        direction: Direction::Right,
+    });
+    level.spawns.push(Spawn {
+        x: 0,
+        y: 8,
+        direction: Direction::Down,
+        player_number: None,
+    });
+    level.spawns.push(Spawn {
+        x: 5,
+        y: 8,
+        direction: Direction::Left,
+        player_number: Some(2),
+    });
+
+    level
+}
+
+fn level_frame(renderer: MapRenderer) -> Frame {
+    let mut camera = Camera::new(12, 12);
+    camera.renderer = renderer;
+
+    let mut frame = Frame::new();
+    frame.resize(camera.view_columns(), camera.view_rows());
+    sample_level()
+        .draw(&mut MapView {
+            target: &mut frame,
+            camera: &camera,
+            theme: &Theme::classic(),
+            horizontal_margin: 0,
+            vertical_margin: 0,
+        })
+        .unwrap();
+
+    frame
+}
+
+fn assert_level_snapshot(name: &str, renderer: MapRenderer) {
+    assert_snapshot(name, &level_frame(renderer));
+}
+
+#[test]
+fn menu() {
+    let mut frame = window_frame();
+    draw(&mut Menu::new(), &mut frame, idle_mouse());
+
+    assert_snapshot("menu", &frame);
+}
+
+#[test]
+fn game_picker_sections() {
+    let mut game_picker = GamePicker::new();
+    let mut frame = window_frame();
+
+    draw(&mut game_picker, &mut frame, idle_mouse());
+    assert_snapshot("game_picker_offline", &frame);
+
+    // Tab of the online section
+    draw(&mut game_picker, &mut frame, click(22, 2));
+    assert_snapshot("game_picker_online", &frame);
+}
+
+#[test]
+fn generator_screen() {
+    let mut generator_screen = GeneratorScreen::with_seed(42);
+    let mut frame = window_frame();
+
+    // Button of four players, which always use quadrant symmetry
+    draw(&mut generator_screen, &mut frame, click(19, 8));
+    draw(&mut generator_screen, &mut frame, idle_mouse());
+    assert_snapshot("generator_screen", &frame);
+}
+
+#[test]
+fn options_screen_themes() {
+    let mut options_screen = OptionsScreen::with_themes(vec![
+        Theme::classic(),
+        Theme::high_contrast(),
+        Theme::color_blind(),
+    ]);
+    let mut frame = window_frame();
+
+    draw(&mut options_screen, &mut frame, idle_mouse());
+    assert_snapshot("options_screen_classic", &frame);
+
+    // Button of the high contrast theme
+    draw(&mut options_screen, &mut frame, click(12, 8));
+    let mut options = Options::new();
+    options_screen.apply_options(&mut options);
+    assert_eq!(options.theme.name, "High contrast");
+}
+
+#[test]
+fn options_screen_settings() {
+    let mut options_screen = OptionsScreen::new();
+    let mut frame = window_frame();
+    let mut options = Options::new();
+
+    // Button of the compact renderer
+    draw(&mut options_screen, &mut frame, click(70, 22));
+    options_screen.apply_options(&mut options);
+    assert!(options.map_rendering == MapRendering::Compact);
+
+    // Button of the 16 color palette
+    draw(&mut options_screen, &mut frame, click(81, 28));
+    options_screen.apply_options(&mut options);
+    assert!(options.color_palette == ColorPalette::Ansi16);
+
+    // ASCII only turned on and off again
+    draw(&mut options_screen, &mut frame, click(57, 34));
+    options_screen.apply_options(&mut options);
+    assert!(options.ascii_only);
+
+    draw(&mut options_screen, &mut frame, click(51, 34));
+    options_screen.apply_options(&mut options);
+    assert!(!options.ascii_only);
+}
+
+#[test]
+fn editor() {
+    let mut frame = window_frame();
+    draw(
+        &mut Editor::with_level(sample_level()),
+        &mut frame,
+        idle_mouse(),
+    );
+
+    assert_snapshot("editor", &frame);
+}
+
+#[test]
+fn editor_paged_sidebar() {
+    let mut editor = Editor::with_level(sample_level());
+    let mut frame = Frame::new();
+    frame.resize(80, 24);
+
+    draw_in_window(&mut editor, &mut frame, 80, 24, idle_mouse());
+    assert_snapshot("editor_paged_tools", &frame);
+
+    // Tab of the level page, next to the compact map
+    draw_in_window(&mut editor, &mut frame, 80, 24, click(68, 23));
+    assert_snapshot("editor_paged_level", &frame);
+}
+
+#[test]
+fn level_full() {
+    assert_level_snapshot("level_full", MapRenderer::Full);
+}
+
+#[test]
+fn level_compact() {
+    assert_level_snapshot("level_compact", MapRenderer::Compact);
+}
+
+#[test]
+fn level_braille() {
+    assert_level_snapshot("level_braille", MapRenderer::Braille);
+}
+
+#[test]
+fn level_ascii() {
+    assert_level_snapshot("level_ascii", MapRenderer::Ascii);
+
+    // Drawn in ASCII by the renderer itself, not only after the terminal's fallback
+    assert!(level_frame(MapRenderer::Ascii).snapshot().is_ascii());
+}
@@ -0,0 +1,185 @@
+use super::render_target::RenderTarget;
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    queue,
+    style::{Attribute, Print, SetAttribute},
+    Result,
+};
+use unicode_width::UnicodeWidthChar;
+
+pub enum TextFieldEvent {
+    Editing,
+    Submitted,
+    Cancelled,
+}
+
+pub struct TextField {
+    pub value: String,
+    cursor_position: usize, // Measured in chars, not bytes
+    max_length: usize,
+}
+
+impl TextField {
+    pub fn new(value: &str, max_length: usize) -> Self {
+        let value: String = value.chars().take(max_length).collect();
+
+        TextField {
+            cursor_position: value.chars().count(),
+            value,
+            max_length,
+        }
+    }
+
+    // Handles the events up to the one which ends the editing. Events after it are returned, so
+    // that keys pressed in the same frame still reach the rest of the mode.
+    pub fn handle_key_events<'a>(
+        &mut self,
+        key_events: &'a [KeyEvent],
+    ) -> (TextFieldEvent, &'a [KeyEvent]) {
+        for (i, key_event) in key_events.iter().enumerate() {
+            match self.handle_key_event(key_event) {
+                TextFieldEvent::Editing => {}
+                event => return (event, &key_events[i + 1..]),
+            }
+        }
+
+        (TextFieldEvent::Editing, &[])
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) -> TextFieldEvent {
+        match key_event.code {
+            KeyCode::Enter => return TextFieldEvent::Submitted,
+            KeyCode::Esc => return TextFieldEvent::Cancelled,
+            KeyCode::Char(c)
+                if !key_event.modifiers.contains(KeyModifiers::CONTROL)
+                    && self.value.chars().count() < self.max_length =>
+            {
+                let index = self.byte_index(self.cursor_position);
+                self.value.insert(index, c);
+                self.cursor_position += 1;
+            }
+            KeyCode::Backspace if self.cursor_position > 0 => {
+                self.cursor_position -= 1;
+                let index = self.byte_index(self.cursor_position);
+                self.value.remove(index);
+            }
+            KeyCode::Delete if self.cursor_position < self.value.chars().count() => {
+                let index = self.byte_index(self.cursor_position);
+                self.value.remove(index);
+            }
+            KeyCode::Left if self.cursor_position > 0 => self.cursor_position -= 1,
+            KeyCode::Right if self.cursor_position < self.value.chars().count() => {
+                self.cursor_position += 1
+            }
+            KeyCode::Home => self.cursor_position = 0,
+            KeyCode::End => self.cursor_position = self.value.chars().count(),
+            _ => {}
+        }
+
+        TextFieldEvent::Editing
+    }
+
+    // Draws a single line of the given width in columns, scrolled so that the cursor is always
+    // visible
+    pub fn draw(&self, target: &mut impl RenderTarget, x: u16, y: u16, width: u16) -> Result<()> {
+        let width = width as usize;
+        let chars: Vec<char> = self.value.chars().collect();
+        let under_cursor = chars.get(self.cursor_position).copied().unwrap_or(' ');
+
+        // As much of the text before the cursor as fits next to it
+        let mut first_visible = self.cursor_position;
+        let mut columns = glyph_width(under_cursor);
+        while first_visible > 0 && columns + glyph_width(chars[first_visible - 1]) <= width {
+            first_visible -= 1;
+            columns += glyph_width(chars[first_visible]);
+        }
+
+        let before: String = chars[first_visible..self.cursor_position].iter().collect();
+        let mut after = String::new();
+        for &c in chars.iter().skip(self.cursor_position + 1) {
+            if columns + glyph_width(c) > width {
+                break;
+            }
+
+            columns += glyph_width(c);
+            after.push(c);
+        }
+        let padding = " ".repeat(width.saturating_sub(columns));
+
+        queue!(
+            target,
+            cursor::MoveTo(x, y),
+            SetAttribute(Attribute::Underlined),
+            Print(before),
+            SetAttribute(Attribute::Reverse),
+            Print(under_cursor),
+            SetAttribute(Attribute::NoReverse),
+            Print(after),
+            Print(padding),
+            SetAttribute(Attribute::NoUnderline),
+        )?;
+
+        Ok(())
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(char_index)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+}
+
+// Columns taken by the glyph, counted like in the frame
+pub fn glyph_width(glyph: char) -> usize {
+    glyph.width().unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::frame::Frame;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn events_after_submission_handed_back() {
+        let mut text_field = TextField::new("ab", 10);
+        let key_events = [
+            key(KeyCode::Char('c')),
+            key(KeyCode::Enter),
+            key(KeyCode::Char('d')),
+            key(KeyCode::Left),
+        ];
+
+        let (event, remaining) = text_field.handle_key_events(&key_events);
+        assert!(matches!(event, TextFieldEvent::Submitted));
+        assert_eq!(text_field.value, "abc");
+        assert_eq!(remaining, &key_events[2..]);
+
+        let (event, remaining) = text_field.handle_key_events(&key_events[2..]);
+        assert!(matches!(event, TextFieldEvent::Editing));
+        assert_eq!(text_field.value, "abcd");
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn wide_glyphs_scrolled_by_columns() {
+        let mut frame = Frame::new();
+        frame.resize(6, 1);
+        let mut text_field = TextField::new("a界界界", 10);
+
+        // Cursor after the text, where only two wide glyphs fit before it
+        text_field.draw(&mut frame, 0, 0, 6).unwrap();
+        assert_eq!(frame.snapshot().lines().next(), Some("界界"));
+
+        // Cursor on the first glyph, the rest cut off at the width
+        text_field.handle_key_events(&[key(KeyCode::Home)]);
+        text_field.draw(&mut frame, 0, 0, 6).unwrap();
+        assert_eq!(frame.snapshot().lines().next(), Some("a界界"));
+    }
+}
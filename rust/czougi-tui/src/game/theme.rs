@@ -0,0 +1,338 @@
+use super::{
+    level::{
+        block::{BlockType, BlockVariant},
+        tank::Direction,
+    },
+    paths::data_dir,
+};
+use crossterm::style::Color;
+use serde::Deserialize;
+use std::{fs::File, io::BufReader, path::Path};
+
+#[derive(Clone, Deserialize)]
+pub struct BlockStyle {
+    pub foreground: Color,
+    pub background: Color,
+    // Two characters for every variant, in the order of `BlockVariant`
+    pub glyphs: [String; 4],
+}
+
+// Four lines of eight characters for every direction the tank can face
+#[derive(Clone, Deserialize)]
+pub struct TankGlyphs {
+    pub up: [String; 4],
+    pub down: [String; 4],
+    pub left: [String; 4],
+    pub right: [String; 4],
+}
+
+#[derive(Clone, Deserialize)]
+pub struct UiColors {
+    pub margin: Color, // Around the screen in windows bigger than needed
+    pub title: Color,
+    pub offline_game_frame: Color,
+    pub online_game_frame: Color,
+}
+
+// Colors and glyphs of everything drawn. Theme files are JSON documents in the themes directory,
+// where missing fields are taken from the classic theme and colors are written like "dark_blue",
+// "ansi_(208)" or "rgb_(119,43,21)".
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub name: String,
+    pub brick: BlockStyle,
+    pub concrete: BlockStyle,
+    pub water: BlockStyle,
+    pub leaves: BlockStyle,
+    // Two characters for every block type in the ASCII renderer, in the order of `BlockType`
+    pub ascii_blocks: [String; 4],
+    pub tank: TankGlyphs,
+    // 'A' is replaced with the letter of the player, as colors are not always enough to tell
+    // tanks apart
+    pub ascii_tank: TankGlyphs,
+    pub players: [Color; 4],
+    pub shared_spawn: Color,
+    pub ui: UiColors,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::classic()
+    }
+}
+
+fn glyphs(left_top: &str, right_top: &str, left_bottom: &str, right_bottom: &str) -> [String; 4] {
+    [left_top, right_top, left_bottom, right_bottom].map(String::from)
+}
+
+fn lines(lines: [&str; 4]) -> [String; 4] {
+    lines.map(String::from)
+}
+
+fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+impl Theme {
+    pub fn classic() -> Self {
+        Theme {
+            name: String::from("Classic"),
+            brick: BlockStyle {
+                foreground: rgb(119, 43, 21),
+                background: rgb(116, 91, 68),
+                glyphs: glyphs("▄▄", "▀▀", "▄▄", "▀▀"),
+            },
+            concrete: BlockStyle {
+                foreground: rgb(196, 196, 196),
+                background: rgb(160, 160, 160),
+                glyphs: glyphs(" ▄", "▄ ", " ▀", "▀ "),
+            },
+            water: BlockStyle {
+                foreground: rgb(66, 66, 255),
+                background: rgb(160, 207, 242),
+                glyphs: glyphs("█▄", "█▀", "▄▄", "▀█"),
+            },
+            leaves: BlockStyle {
+                foreground: rgb(140, 214, 0),
+                background: rgb(0, 82, 8),
+                glyphs: glyphs("█▀", "▄▀", "▄▀", "▄█"),
+            },
+            ascii_blocks: glyphs("##", "@@", "~~", "\"\""),
+            tank: TankGlyphs {
+                up: lines(["   ▐▌   ", "▄▄████▄▄", "████████", "██▀▀▀▀██"]),
+                down: lines(["██▄▄▄▄██", "████████", "▀▀████▀▀", "   ▐▌   "]),
+                left: lines(["   █████", "▄▄█████ ", "▀▀█████ ", "   █████"]),
+                right: lines(["█████   ", " █████▄▄", " █████▀▀", "█████   "]),
+            },
+            ascii_tank: TankGlyphs {
+                up: lines(["   ||   ", "  AAAA  ", "AAAAAAAA", "AAAAAAAA"]),
+                down: lines(["AAAAAAAA", "AAAAAAAA", "  AAAA  ", "   ||   "]),
+                left: lines(["   AAAAA", "==AAAAA ", "==AAAAA ", "   AAAAA"]),
+                right: lines(["AAAAA   ", " AAAAA==", " AAAAA==", "AAAAA   "]),
+            },
+            players: [Color::Yellow, Color::Blue, Color::Green, Color::Red],
+            shared_spawn: Color::Grey,
+            ui: UiColors {
+                margin: Color::DarkBlue,
+                title: Color::Red,
+                offline_game_frame: rgb(24, 204, 36),
+                online_game_frame: rgb(240, 204, 28),
+            },
+        }
+    }
+
+    // Saturated colors far apart in brightness, with blocks of uniform glyphs
+    pub fn high_contrast() -> Self {
+        Theme {
+            name: String::from("High contrast"),
+            brick: BlockStyle {
+                foreground: Color::Black,
+                background: rgb(255, 128, 0),
+                glyphs: glyphs("▄▄", "▀▀", "▄▄", "▀▀"),
+            },
+            concrete: BlockStyle {
+                foreground: Color::Black,
+                background: Color::White,
+                glyphs: glyphs("  ", "  ", "  ", "  "),
+            },
+            water: BlockStyle {
+                foreground: Color::White,
+                background: rgb(0, 0, 255),
+                glyphs: glyphs("~~", "~~", "~~", "~~"),
+            },
+            leaves: BlockStyle {
+                foreground: Color::Black,
+                background: rgb(0, 255, 0),
+                glyphs: glyphs("▚▚", "▚▚", "▚▚", "▚▚"),
+            },
+            players: [Color::Yellow, Color::Cyan, Color::Magenta, Color::White],
+            shared_spawn: Color::White,
+            ui: UiColors {
+                margin: Color::Black,
+                title: Color::White,
+                offline_game_frame: Color::White,
+                online_game_frame: Color::Yellow,
+            },
+            ..Theme::classic()
+        }
+    }
+
+    // Okabe-Ito palette, which stays distinguishable with the common kinds of color blindness
+    pub fn color_blind() -> Self {
+        let classic = Theme::classic();
+
+        Theme {
+            name: String::from("Color-blind safe"),
+            brick: BlockStyle {
+                foreground: rgb(213, 94, 0),
+                background: rgb(110, 50, 0),
+                ..classic.brick
+            },
+            water: BlockStyle {
+                foreground: rgb(0, 114, 178),
+                background: rgb(86, 180, 233),
+                ..classic.water
+            },
+            leaves: BlockStyle {
+                foreground: rgb(0, 158, 115),
+                background: rgb(0, 70, 50),
+                ..classic.leaves
+            },
+            players: [
+                rgb(240, 228, 66),
+                rgb(86, 180, 233),
+                rgb(204, 121, 167),
+                rgb(230, 159, 0),
+            ],
+            ui: UiColors {
+                offline_game_frame: rgb(0, 158, 115),
+                online_game_frame: rgb(230, 159, 0),
+                ..classic.ui
+            },
+            ..classic
+        }
+    }
+
+    // Built-in themes followed by the ones in the themes directory, skipping files that cannot be
+    // read
+    pub fn all() -> Vec<Self> {
+        let mut themes = vec![
+            Theme::classic(),
+            Theme::high_contrast(),
+            Theme::color_blind(),
+        ];
+
+        if let Some(Ok(entries)) = data_dir().map(|dir| dir.join("themes").read_dir()) {
+            let mut paths: Vec<_> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == "json")
+                })
+                .collect();
+            paths.sort();
+
+            themes.extend(paths.iter().filter_map(|path| Theme::load(path)));
+        }
+
+        themes
+    }
+
+    pub fn load(path: &Path) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        serde_json::from_reader(BufReader::new(file)).ok()
+    }
+
+    pub fn block(&self, block_type: BlockType) -> &BlockStyle {
+        match block_type {
+            BlockType::Brick => &self.brick,
+            BlockType::Concrete => &self.concrete,
+            BlockType::Water => &self.water,
+            BlockType::Leaves => &self.leaves,
+        }
+    }
+
+    pub fn ascii_block(&self, block_type: BlockType) -> &str {
+        let index = match block_type {
+            BlockType::Brick => 0,
+            BlockType::Concrete => 1,
+            BlockType::Water => 2,
+            BlockType::Leaves => 3,
+        };
+
+        &self.ascii_blocks[index]
+    }
+
+    pub fn player_color(&self, player_number: u8) -> Color {
+        self.players[player_number as usize]
+    }
+
+    // Spawns without a player are shared by all of them
+    pub fn spawn_color(&self, player_number: Option<u8>) -> Color {
+        player_number.map_or(self.shared_spawn, |player_number| {
+            self.player_color(player_number)
+        })
+    }
+}
+
+impl BlockStyle {
+    pub fn glyphs(&self, block_variant: BlockVariant) -> &str {
+        let index = match block_variant {
+            BlockVariant::LeftTop => 0,
+            BlockVariant::RightTop => 1,
+            BlockVariant::LeftBottom => 2,
+            BlockVariant::RightBottom => 3,
+        };
+
+        &self.glyphs[index]
+    }
+}
+
+impl TankGlyphs {
+    pub fn lines(&self, direction: Direction) -> &[String; 4] {
+        match direction {
+            Direction::Up => &self.up,
+            Direction::Down => &self.down,
+            Direction::Left => &self.left,
+            Direction::Right => &self.right,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_taken_from_classic() {
+        let theme: Theme = serde_json::from_str(
+            r#"{
+                "name": "Night",
+                "water": {
+                    "foreground": "dark_blue",
+                    "background": "rgb_(0,0,40)",
+                    "glyphs": ["~~", "~~", "~~", "~~"]
+                },
+                "players": ["white", "cyan", "ansi_(208)", "magenta"]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(theme.name, "Night");
+        assert_eq!(theme.water.background, rgb(0, 0, 40));
+        assert_eq!(theme.water.glyphs(BlockVariant::RightBottom), "~~");
+        assert_eq!(theme.player_color(2), Color::AnsiValue(208));
+        assert_eq!(theme.brick.foreground, Theme::classic().brick.foreground);
+        assert_eq!(theme.ui.margin, Color::DarkBlue);
+    }
+
+    #[test]
+    fn glyph_sets_from_file() {
+        let theme: Theme = serde_json::from_str(
+            r#"{
+                "ascii_blocks": ["%%", "[]", "==", "**"],
+                "tank": {
+                    "up": ["   ||   ", " |XXXX| ", " |XXXX| ", " |XXXX| "],
+                    "down": [" |XXXX| ", " |XXXX| ", " |XXXX| ", "   ||   "],
+                    "left": ["  =====", "==XXXX| ", "==XXXX| ", "  =====  "],
+                    "right": ["=====  ", " |XXXX==", " |XXXX==", "=====  "]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(theme.ascii_block(BlockType::Concrete), "[]");
+        assert_eq!(theme.tank.lines(Direction::Down)[3], "   ||   ");
+        assert_eq!(
+            theme.ascii_tank.lines(Direction::Up),
+            Theme::classic().ascii_tank.lines(Direction::Up)
+        );
+    }
+
+    #[test]
+    fn invalid_color_rejected() {
+        let theme = serde_json::from_str::<Theme>(r#"{ "shared_spawn": "purple" }"#);
+        assert!(theme.is_err());
+    }
+}